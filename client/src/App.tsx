@@ -13,7 +13,6 @@
  * 2. Player Input Handling:
  *    - Keyboard and mouse event listeners
  *    - Input state tracking and normalization
- *    - Animation state determination
  *    - Camera/rotation management with pointer lock
  * 
  * 3. Game Loop:
@@ -24,12 +23,12 @@
  * 4. UI Management:
  *    - Renders GameScene (3D view)
  *    - Controls DebugPanel visibility
- *    - Manages JoinGameDialog for player registration
+ *    - Manages JoinGameDialog for character creation and selection
  *    - Displays connection status
  * 
  * Extension points:
  *    - Add new input types in currentInputRef and InputState
- *    - Extend determineAnimation for new animation states
+ *    - Extend the server animation state machine (server/src/animation.rs) for new animation states
  *    - Add new reducers calls for game features (see handleCastSpellInput)
  *    - Modify game loop timing or prediction logic
  * 
@@ -56,6 +55,12 @@ type EventContext = moduleBindings.EventContext;
 type ErrorContext = moduleBindings.ErrorContext;
 type PlayerData = moduleBindings.PlayerData;
 type InputState = moduleBindings.InputState;
+type PaletteColor = moduleBindings.PaletteColor;
+type Character = moduleBindings.Character;
+type TerrainConfig = moduleBindings.TerrainConfig;
+type TerrainChunk = moduleBindings.TerrainChunk;
+type Obstacle = moduleBindings.Obstacle;
+type RejectedCall = moduleBindings.RejectedCall;
 // ... other types ...
 
 let conn: DbConnection | null = null;
@@ -67,6 +72,12 @@ function App() {
   const [players, setPlayers] = useState<ReadonlyMap<string, PlayerData>>(new Map());
   const [localPlayer, setLocalPlayer] = useState<PlayerData | null>(null);
   const [showJoinDialog, setShowJoinDialog] = useState(false);
+  const [joinError, setJoinError] = useState<string | null>(null);
+  const [palette, setPalette] = useState<PaletteColor[]>([]);
+  const [roster, setRoster] = useState<ReadonlyMap<bigint, Character>>(new Map());
+  const [terrainConfig, setTerrainConfig] = useState<TerrainConfig | null>(null);
+  const [terrainChunks, setTerrainChunks] = useState<ReadonlyMap<bigint, TerrainChunk>>(new Map());
+  const [obstacles, setObstacles] = useState<ReadonlyMap<bigint, Obstacle>>(new Map());
   const [isDebugPanelExpanded, setIsDebugPanelExpanded] = useState(false);
   const [isPointerLocked, setIsPointerLocked] = useState(false); // State for pointer lock status
 
@@ -119,11 +130,78 @@ function App() {
             setStatusMessage("Local player deleted!");
         }
     });
+    // Keep the character roster in sync for the join dialog
+    conn.db.characterRoster.onInsert((_ctx: EventContext, character: Character) => {
+        setRoster((prev: ReadonlyMap<bigint, Character>) => new Map(prev).set(character.characterId, character));
+    });
+    conn.db.characterRoster.onUpdate((_ctx: EventContext, _old: Character, character: Character) => {
+        setRoster((prev: ReadonlyMap<bigint, Character>) => new Map(prev).set(character.characterId, character));
+    });
+    conn.db.characterRoster.onDelete((_ctx: EventContext, character: Character) => {
+        setRoster((prev: ReadonlyMap<bigint, Character>) => {
+            const newMap = new Map(prev);
+            newMap.delete(character.characterId);
+            return newMap;
+        });
+    });
+    // Rejected calls still succeed (so they cost a rate limit token); the server reports them
+    // in rejected_call instead. Reopen the join dialog if one of our roster actions is rejected.
+    const rosterActions: Record<string, string> = {
+        create_character: "Character creation",
+        delete_character: "Character deletion",
+        select_character: "Character selection",
+    };
+    const onRejectedCall = (rejection: RejectedCall) => {
+        const action = rosterActions[rejection.reducer];
+        if (!action || !identity || rejection.identity.toHexString() !== identity.toHexString()) return;
+        console.warn(`${action} rejected:`, rejection.message);
+        setJoinError(rejection.message);
+        setShowJoinDialog(true);
+    };
+    // Terrain is generated once, but keep it in sync if it is edited on the server
+    conn.db.terrainConfig.onInsert((_ctx: EventContext, config: TerrainConfig) => setTerrainConfig(config));
+    conn.db.terrainConfig.onUpdate((_ctx: EventContext, _old: TerrainConfig, config: TerrainConfig) => setTerrainConfig(config));
+    conn.db.terrainChunk.onInsert((_ctx: EventContext, chunk: TerrainChunk) => {
+        setTerrainChunks((prev: ReadonlyMap<bigint, TerrainChunk>) => new Map(prev).set(chunk.chunkId, chunk));
+    });
+    conn.db.terrainChunk.onUpdate((_ctx: EventContext, _old: TerrainChunk, chunk: TerrainChunk) => {
+        setTerrainChunks((prev: ReadonlyMap<bigint, TerrainChunk>) => new Map(prev).set(chunk.chunkId, chunk));
+    });
+    conn.db.terrainChunk.onDelete((_ctx: EventContext, chunk: TerrainChunk) => {
+        setTerrainChunks((prev: ReadonlyMap<bigint, TerrainChunk>) => {
+            const newMap = new Map(prev);
+            newMap.delete(chunk.chunkId);
+            return newMap;
+        });
+    });
+    // Obstacles can be added and removed by admins at runtime
+    conn.db.obstacle.onInsert((_ctx: EventContext, obstacle: Obstacle) => {
+        setObstacles((prev: ReadonlyMap<bigint, Obstacle>) => new Map(prev).set(obstacle.obstacleId, obstacle));
+    });
+    conn.db.obstacle.onDelete((_ctx: EventContext, obstacle: Obstacle) => {
+        setObstacles((prev: ReadonlyMap<bigint, Obstacle>) => {
+            const newMap = new Map(prev);
+            newMap.delete(obstacle.obstacleId);
+            return newMap;
+        });
+    });
+    conn.db.rejectedCall.onInsert((ctx: EventContext, rejection: RejectedCall) => {
+        // Ignore a rejection left over from before we connected
+        if (ctx.event.tag !== 'SubscribeApplied') onRejectedCall(rejection);
+    });
+    conn.db.rejectedCall.onUpdate((_ctx: EventContext, _old: RejectedCall, rejection: RejectedCall) => onRejectedCall(rejection));
     console.log("Table callbacks registered.");
   }, [identity]); // Keep identity dependency
 
   const onSubscriptionApplied = useCallback(() => {
      console.log("Subscription applied successfully.");
+     if (conn) {
+         setPalette([...conn.db.colorPalette.iter()].sort((a, b) => a.sortOrder - b.sortOrder));
+         setRoster(new Map([...conn.db.characterRoster.iter()].map((c) => [c.characterId, c] as const)));
+         setTerrainConfig(conn.db.terrainConfig.id.find(0) ?? null);
+         setTerrainChunks(new Map([...conn.db.terrainChunk.iter()].map((c) => [c.chunkId, c] as const)));
+         setObstacles(new Map([...conn.db.obstacle.iter()].map((o) => [o.obstacleId, o] as const)));
+     }
      setPlayers((prev: ReadonlyMap<string, PlayerData>) => {
          if (prev.size === 0 && conn) {
              const currentPlayers = new Map<string, PlayerData>();
@@ -148,7 +226,15 @@ function App() {
     if (!conn) return;
     console.log("Subscribing to tables...");
     const subscription = conn.subscriptionBuilder();
-    subscription.subscribe("SELECT * FROM player");
+    subscription.subscribe([
+        "SELECT * FROM player",
+        "SELECT * FROM color_palette",
+        "SELECT * FROM character_roster",
+        "SELECT * FROM terrain_config",
+        "SELECT * FROM terrain_chunk",
+        "SELECT * FROM obstacle",
+        "SELECT * FROM rejected_call",
+    ]);
     subscription.onApplied(onSubscriptionApplied);
     subscription.onError(onSubscriptionError);
   }, [identity, onSubscriptionApplied, onSubscriptionError]); // Add dependencies
@@ -169,50 +255,6 @@ function App() {
       ShiftLeft: 'sprint', Space: 'jump',
   };
 
-  const determineAnimation = useCallback((input: InputState): string => {
-    if (input.attack) return 'attack1';
-    if (input.castSpell) return 'cast';
-    if (input.jump) return 'jump';
-    
-    // Determine animation based on movement keys
-    const { forward, backward, left, right, sprint } = input;
-    const isMoving = forward || backward || left || right;
-    
-    if (!isMoving) return 'idle';
-    
-    // Improved direction determination with priority handling
-    // This matches legacy implementation better
-    let direction = 'forward';
-    
-    // Primary direction determination - match legacy player.js logic
-    if (forward && !backward) {
-      direction = 'forward';
-    } else if (backward && !forward) {
-      direction = 'back';
-    } else if (left && !right) {
-      direction = 'left';
-    } else if (right && !left) {
-      direction = 'right';
-    } else if (forward && left) {
-      // Handle diagonal movement by choosing dominant direction
-      direction = 'left';
-    } else if (forward && right) {
-      direction = 'right'; 
-    } else if (backward && left) {
-      direction = 'left';
-    } else if (backward && right) {
-      direction = 'right';
-    }
-    
-    // Choose movement type based on sprint state
-    const moveType = sprint ? 'run' : 'walk';
-    
-    // Generate final animation name
-    const animationName = `${moveType}-${direction}`;
-    
-    return animationName;
-  }, []);
-
   const sendInput = useCallback((currentInputState: InputState) => {
     if (!conn || !identity || !connected) return; // Check connection status too
     const currentPosition = localPlayer?.position || { x: 0, y: 0, z: 0 };
@@ -223,9 +265,6 @@ function App() {
       y: playerRotationRef.current.y,
       z: playerRotationRef.current.z
     };
-    
-    // Determine animation from input state
-    const currentAnimation = determineAnimation(currentInputState);
 
     let changed = false;
     for (const key in currentInputState) {
@@ -236,10 +275,10 @@ function App() {
     }
 
     if (changed || currentInputState.sequence !== lastSentInputState.current.sequence) {
-        conn.reducers.updatePlayerInput(currentInputState, currentPosition, currentRotation, currentAnimation);
+        conn.reducers.updatePlayerInput(currentInputState, currentPosition, currentRotation);
         lastSentInputState.current = { ...currentInputState };
     }
-  }, [identity, localPlayer, connected, determineAnimation]);
+  }, [identity, localPlayer, connected]);
 
   // Add player rotation handler
   const handlePlayerRotation = useCallback((rotation: THREE.Euler) => {
@@ -418,21 +457,52 @@ function App() {
     };
   }, []);
 
-  // --- handleJoinGame ---
-  const handleJoinGame = (username: string, characterClass: string) => {
+  // --- Character Roster Handlers ---
+  const handleCreateCharacter = (name: string, characterClass: string, color?: string) => {
+    if (!conn) {
+        console.error("Cannot create character, not connected.");
+        return;
+    }
+    console.log(`Creating character ${name} (${characterClass})...`);
+    // Leaving color undefined lets the server use the profile or least-used palette color
+    conn.reducers.createCharacter(name, characterClass, color);
+    setJoinError(null);
+  };
+
+  const handleDeleteCharacter = (characterId: bigint) => {
+    if (!conn) return;
+    conn.reducers.deleteCharacter(characterId);
+    setJoinError(null);
+  };
+
+  const handleSelectCharacter = (characterId: bigint) => {
     if (!conn) {
         console.error("Cannot join game, not connected.");
         return;
     }
-    console.log(`Registering as ${username} (${characterClass})...`);
-    conn.reducers.registerPlayer(username, characterClass);
+    console.log(`Entering the world as character ${characterId}...`);
+    conn.reducers.selectCharacter(characterId);
+    setJoinError(null);
     setShowJoinDialog(false);
   };
 
+  const ownCharacters = identity
+    ? [...roster.values()].filter((c) => c.owner.toHexString() === identity.toHexString())
+    : [];
+
   // --- Render Logic ---
   return (
     <div className="App" style={{ width: '100vw', height: '100vh', position: 'relative' }}>
-      {showJoinDialog && <JoinGameDialog onJoin={handleJoinGame} />}
+      {showJoinDialog && (
+          <JoinGameDialog
+            characters={ownCharacters}
+            palette={palette}
+            error={joinError}
+            onCreate={handleCreateCharacter}
+            onDelete={handleDeleteCharacter}
+            onSelect={handleSelectCharacter}
+          />
+      )}
       
       {/* Conditionally render DebugPanel based on connection status */} 
       {/* Visibility controlled internally, expansion controlled by state */}
@@ -457,6 +527,9 @@ function App() {
             onPlayerRotation={handlePlayerRotation}
             currentInputRef={currentInputRef}
             isDebugPanelVisible={isDebugPanelExpanded}
+            terrainConfig={terrainConfig}
+            terrainChunks={terrainChunks}
+            obstacles={obstacles}
           />
           {/* Render PlayerUI only if localPlayer exists */} 
           {localPlayer && <PlayerUI playerData={localPlayer} />} 
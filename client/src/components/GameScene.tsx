@@ -25,20 +25,24 @@
  * 
  * Related files:
  * - Player.tsx: Individual player entity component
+ * - Terrain.tsx: Heightmap meshes and ground height sampling
+ * - Obstacles.tsx: Static collision shapes
  * - JoinGameDialog.tsx: UI for joining a game session
  * - PlayerUI.tsx: In-game user interface elements
  * - Socket handlers for network communication
  */
 
-import React, { useRef } from 'react';
+import React, { useCallback, useRef } from 'react';
 import { Canvas } from '@react-three/fiber';
-import { Box, Plane, Grid, Sky } from '@react-three/drei';
+import { Box, Plane, Sky } from '@react-three/drei';
 import * as THREE from 'three';
 import { DirectionalLightHelper, CameraHelper } from 'three'; // Import the helper
 // Import generated types
-import { PlayerData, InputState } from '../generated';
+import { PlayerData, InputState, TerrainConfig, TerrainChunk, Obstacle } from '../generated';
 import { Identity } from '@clockworklabs/spacetimedb-sdk';
 import { Player } from './Player';
+import { Terrain, sampleTerrainHeight } from './Terrain';
+import { Obstacles } from './Obstacles';
 
 interface GameSceneProps {
   players: ReadonlyMap<string, PlayerData>; // Receive the map
@@ -46,6 +50,9 @@ interface GameSceneProps {
   onPlayerRotation?: (rotation: THREE.Euler) => void; // Optional callback for player rotation
   currentInputRef?: React.MutableRefObject<InputState>; // Add input state ref prop
   isDebugPanelVisible?: boolean; // Prop to indicate if the debug panel is visible
+  terrainConfig: TerrainConfig | null;
+  terrainChunks: ReadonlyMap<bigint, TerrainChunk>;
+  obstacles: ReadonlyMap<bigint, Obstacle>;
 }
 
 export const GameScene: React.FC<GameSceneProps> = ({ 
@@ -53,11 +60,20 @@ export const GameScene: React.FC<GameSceneProps> = ({
   localPlayerIdentity,
   onPlayerRotation,
   currentInputRef, // Receive input state ref
-  isDebugPanelVisible = false // Destructure the new prop
+  isDebugPanelVisible = false, // Destructure the new prop
+  terrainConfig,
+  terrainChunks,
+  obstacles,
 }) => {
   // Ref for the main directional light
   const directionalLightRef = useRef<THREE.DirectionalLight>(null!); 
 
+  // Lets the local player's prediction follow the same ground as the server
+  const groundHeightAt = useCallback(
+    (x: number, z: number) => sampleTerrainHeight(terrainConfig, terrainChunks, x, z),
+    [terrainConfig, terrainChunks]
+  );
+
   return (
     <Canvas 
       camera={{ position: [0, 10, 20], fov: 60 }} 
@@ -99,24 +115,19 @@ export const GameScene: React.FC<GameSceneProps> = ({
         </>
       )}
       
-      {/* Visible Background Plane (darker, receives shadows) */}
+      {/* Flat ground beyond the heightmap (default terrain height, receives shadows) */}
       <Plane 
-        args={[200, 200]} 
+        args={[1000, 1000]} 
         rotation={[-Math.PI / 2, 0, 0]} 
-        position={[0, -0.001, 0]} 
+        position={[0, (terrainConfig?.defaultHeight ?? 0) - 0.01, 0]} 
         receiveShadow={true} 
       >
         <meshStandardMaterial color="#606060" /> { /* Changed to darker gray */ }
       </Plane>
-o
-      {/* Simplified Grid Helper (mid-gray lines) */}
-      <Grid 
-        position={[0, 0, 0]} 
-        args={[200, 200]} 
-        cellSize={2} 
-        cellThickness={1}
-        cellColor={new THREE.Color('#888888')} // Mid-gray grid lines
-      />
+
+      {/* Heightmap terrain streamed from the server */}
+      <Terrain config={terrainConfig} chunks={terrainChunks} />
+      <Obstacles obstacles={obstacles} />
 
       {/* Render Players */}
       {Array.from(players.values()).map((player) => {
@@ -130,6 +141,7 @@ o
             currentInput={isLocal ? currentInputRef?.current : undefined}
             isDebugArrowVisible={isLocal ? isDebugPanelVisible : false} // Pass down arrow visibility
             isDebugPanelVisible={isDebugPanelVisible} // Pass down general debug visibility
+            groundHeightAt={isLocal ? groundHeightAt : undefined}
           />
         );
       })}
@@ -4,16 +4,20 @@
  * Entry point component for the multiplayer game experience:
  * 
  * Key functionality:
- * - Provides a UI for player name entry and character selection
+ * - Lists the player's characters with Play / Delete actions (roster from character_roster)
+ * - Provides a form for creating a character (name, class, color)
  * - Validates user input before allowing game entry
  * - Displays character class options with visual previews
  * - Handles initial connection to the game server
  * - Manages the transition from lobby to active gameplay
  * 
  * Props:
- * - onJoinGame: Callback function that passes player information to parent component
- * - isOpen: Boolean to control dialog visibility
- * - onClose: Function to handle dialog dismissal
+ * - characters: The local identity's roster entries
+ * - onCreate: Calls the create_character reducer (name, class, optional color)
+ * - onSelect: Calls select_character to enter the world
+ * - onDelete: Calls delete_character
+ * - error: Server rejection message (e.g. invalid or taken name) shown above the form
+ * - palette: Colors from the server's color_palette table; "Auto" lets the server choose
  * 
  * Technical implementation:
  * - Uses Chakra UI components for responsive, accessible interface
@@ -28,25 +32,69 @@
  */
 
 import React, { useState, Suspense } from 'react';
+import { Character, PaletteColor } from '../generated';
 
 interface JoinGameDialogProps {
-  onJoin: (username: string, characterClass: string) => void;
+  characters: Character[];
+  onCreate: (name: string, characterClass: string, color?: string) => void;
+  onSelect: (characterId: bigint) => void;
+  onDelete: (characterId: bigint) => void;
+  error?: string | null; // Rejection message from the server, if the last attempt failed
+  palette?: PaletteColor[];
 }
 
-export const JoinGameDialog: React.FC<JoinGameDialogProps> = ({ onJoin }) => {
+export const JoinGameDialog: React.FC<JoinGameDialogProps> = ({
+  characters,
+  onCreate,
+  onSelect,
+  onDelete,
+  error,
+  palette = [],
+}) => {
   const [username, setUsername] = useState('Adventurer');
   const [characterClass, setCharacterClass] = useState('Wizard');
+  const [color, setColor] = useState(''); // Empty means "Auto"
 
   const handleSubmit = (event: React.FormEvent) => {
     event.preventDefault();
     const finalUsername = username.trim() || `Player${Math.floor(Math.random() * 1000)}`;
-    onJoin(finalUsername, characterClass);
+    onCreate(finalUsername, characterClass, color || undefined);
   };
 
   return (
     <div style={styles.overlay}>
       <form style={styles.dialog} onSubmit={handleSubmit}>
         <h2>Join Game</h2>
+        {error && <div style={styles.error}>{error}</div>}
+        {characters.length > 0 && (
+          <div style={styles.inputGroup}>
+            <span style={styles.label}>Your Characters:</span>
+            {characters.map((character) => (
+              <div key={character.characterId.toString()} style={styles.characterRow}>
+                <span style={{ color: character.color }}>
+                  {character.name} ({character.characterClass})
+                </span>
+                <span>
+                  <button type="button" style={styles.smallButton} onClick={() => onSelect(character.characterId)}>
+                    Play
+                  </button>
+                  <button
+                    type="button"
+                    style={{ ...styles.smallButton, backgroundColor: '#a94442' }}
+                    onClick={() => {
+                      if (window.confirm(`Delete ${character.name}? This cannot be undone.`)) {
+                        onDelete(character.characterId);
+                      }
+                    }}
+                  >
+                    Delete
+                  </button>
+                </span>
+              </div>
+            ))}
+          </div>
+        )}
+        <h3>New Character</h3>
         <div style={styles.inputGroup}>
           <label htmlFor="username" style={styles.label}>Character Name:</label>
           <input
@@ -71,7 +119,23 @@ export const JoinGameDialog: React.FC<JoinGameDialogProps> = ({ onJoin }) => {
             {/* Add more classes later */}
           </select>
         </div>
-        <button type="submit" style={styles.button}>Join Game</button>
+        <div style={styles.inputGroup}>
+          <label htmlFor="color" style={styles.label}>Color:</label>
+          <select
+            id="color"
+            value={color}
+            onChange={(e) => setColor(e.target.value)}
+            style={{ ...styles.select, color: color || '#eee' }}
+          >
+            <option value="">Auto</option>
+            {palette.map((entry) => (
+              <option key={entry.value} value={entry.value} style={{ color: entry.value }}>
+                {entry.label}
+              </option>
+            ))}
+          </select>
+        </div>
+        <button type="submit" style={styles.button}>Create Character</button>
       </form>
     </div>
   );
@@ -91,6 +155,10 @@ const styles: { [key: string]: React.CSSProperties } = {
     alignItems: 'center',
     zIndex: 1000,
   },
+  error: {
+    color: '#ff6b6b',
+    marginBottom: '15px',
+  },
   dialog: {
     backgroundColor: '#2a2a3a',
     padding: '30px',
@@ -129,6 +197,22 @@ const styles: { [key: string]: React.CSSProperties } = {
      color: '#eee',
      fontSize: '16px',
   },
+  characterRow: {
+    display: 'flex',
+    justifyContent: 'space-between',
+    alignItems: 'center',
+    marginBottom: '8px',
+  },
+  smallButton: {
+    padding: '6px 12px',
+    marginLeft: '6px',
+    border: 'none',
+    borderRadius: '4px',
+    backgroundColor: '#4a90e2',
+    color: 'white',
+    fontSize: '14px',
+    cursor: 'pointer',
+  },
   button: {
     padding: '12px 25px',
     border: 'none',
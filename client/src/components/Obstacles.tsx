@@ -0,0 +1,68 @@
+/**
+ * Obstacles.tsx
+ *
+ * Renders the server's static collision shapes (obstacle table).
+ *
+ * Key functionality:
+ * - Aabb: Box sized from halfExtents
+ * - Cylinder: Upright cylinder of radius / height
+ * - Sphere: Sphere of radius
+ *
+ * Props:
+ * - obstacles: obstacle rows keyed by obstacleId
+ *
+ * Related files:
+ * - GameScene.tsx: Renders this component
+ * - server/src/collision.rs: Shape definitions and collision resolution
+ */
+
+import React from 'react';
+import { Box, Cylinder, Sphere } from '@react-three/drei';
+import { Obstacle } from '../generated';
+
+interface ObstaclesProps {
+  obstacles: ReadonlyMap<bigint, Obstacle>;
+}
+
+const OBSTACLE_COLOR = '#8a8178';
+
+const ObstacleMesh: React.FC<{ obstacle: Obstacle }> = ({ obstacle }) => {
+  const position: [number, number, number] = [obstacle.center.x, obstacle.center.y, obstacle.center.z];
+  const material = <meshStandardMaterial color={OBSTACLE_COLOR} />;
+
+  switch (obstacle.shape.tag) {
+    case 'Aabb':
+      return (
+        <Box
+          args={[obstacle.halfExtents.x * 2, obstacle.halfExtents.y * 2, obstacle.halfExtents.z * 2]}
+          position={position}
+          castShadow
+          receiveShadow
+        >
+          {material}
+        </Box>
+      );
+    case 'Cylinder':
+      return (
+        <Cylinder args={[obstacle.radius, obstacle.radius, obstacle.height, 24]} position={position} castShadow receiveShadow>
+          {material}
+        </Cylinder>
+      );
+    case 'Sphere':
+      return (
+        <Sphere args={[obstacle.radius, 32, 16]} position={position} castShadow receiveShadow>
+          {material}
+        </Sphere>
+      );
+    default:
+      return null;
+  }
+};
+
+export const Obstacles: React.FC<ObstaclesProps> = ({ obstacles }) => (
+  <group>
+    {Array.from(obstacles.values()).map((obstacle) => (
+      <ObstacleMesh key={obstacle.obstacleId.toString()} obstacle={obstacle} />
+    ))}
+  </group>
+);
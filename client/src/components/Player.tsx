@@ -59,10 +59,19 @@ const ANIMATIONS = {
 // --- Client-side Constants ---
 const PLAYER_SPEED = 5.0; // Match server logic
 const SPRINT_MULTIPLIER = 1.8; // Match server logic
+// Vertical physics (match server/src/common.rs)
+const GRAVITY = 20.0;
+const JUMP_VELOCITY = 8.0;
+const TERMINAL_VELOCITY = 40.0;
+const COYOTE_TIME_SECONDS = 0.12;
+const GROUND_SNAP_DISTANCE = 0.5;
 
 // --- Client-side Prediction Constants ---
-const SERVER_TICK_RATE = 60; // Assuming server runs at 60Hz
-const SERVER_TICK_DELTA = 1 / SERVER_TICK_RATE; // Use this for prediction
+// Prediction runs in the server's fixed steps (match SIMULATION_TICK_RATE_HZ and
+// MAX_CATCH_UP_STEPS in server/src/common.rs) so jump arcs come out identical
+const SIMULATION_TICK_RATE_HZ = 30;
+const SIMULATION_STEP = 1 / SIMULATION_TICK_RATE_HZ;
+const MAX_CATCH_UP_STEPS = 4;
 const POSITION_RECONCILE_THRESHOLD = 0.4;
 const ROTATION_RECONCILE_THRESHOLD = 0.1; // Radians
 const RECONCILE_LERP_FACTOR = 0.15;
@@ -80,6 +89,7 @@ interface PlayerProps {
   currentInput?: InputState; // Prop to receive current input for local player
   isDebugArrowVisible?: boolean; // Prop to control debug arrow visibility
   isDebugPanelVisible?: boolean; // Prop to control general debug helpers visibility
+  groundHeightAt?: (x: number, z: number) => number; // Terrain height sampler for local prediction
 }
 
 export const Player: React.FC<PlayerProps> = ({
@@ -88,7 +98,8 @@ export const Player: React.FC<PlayerProps> = ({
   onRotationChange,
   currentInput, // Receive input state
   isDebugArrowVisible = false, 
-  isDebugPanelVisible = false // Destructure with default false
+  isDebugPanelVisible = false, // Destructure with default false
+  groundHeightAt,
 }) => {
   const group = useRef<THREE.Group>(null!);
   const { camera } = useThree();
@@ -105,6 +116,17 @@ export const Player: React.FC<PlayerProps> = ({
   // --- Client Prediction State ---
   const localPositionRef = useRef<THREE.Vector3>(new THREE.Vector3(playerData.position.x, playerData.position.y, playerData.position.z));
   const localRotationRef = useRef<THREE.Euler>(new THREE.Euler(0, 0, 0, 'YXZ')); // Initialize with zero rotation
+  // Predicted vertical state, mirroring player_logic::step_vertical on the server
+  const verticalRef = useRef({
+    velocity: playerData.verticalVelocity,
+    isGrounded: playerData.isGrounded,
+    airborneSeconds: playerData.airborneSeconds,
+  });
+  // Frame time not yet consumed by a fixed prediction step
+  const predictionAccumulatorRef = useRef(0);
+  // Jumps fire on the press, like jump_requested on the server
+  const jumpRequestedRef = useRef(false);
+  const previousJumpRef = useRef(false);
   const debugArrowRef = useRef<THREE.ArrowHelper | null>(null); // Declare the ref for the debug arrow
   
   // Camera control variables
@@ -855,14 +877,56 @@ export const Player: React.FC<PlayerProps> = ({
         if (isLocalPlayer && currentInput) {
           // --- LOCAL PLAYER PREDICTION & RECONCILIATION --- 
 
-          // 1. Calculate predicted position based on current input, rotation, and SERVER_TICK_DELTA
-          const predictedPosition = calculateClientMovement(
-            localPositionRef.current,
-            localRotationRef.current, // Pass current local rotation; function internally selects based on mode
-            currentInput,
-            SERVER_TICK_DELTA // Use FIXED delta for prediction to match server
+          if (currentInput.jump && !previousJumpRef.current) {
+            jumpRequestedRef.current = true;
+          }
+          previousJumpRef.current = currentInput.jump;
+
+          // 1. Advance the prediction in whole fixed steps, like player_logic::simulate_step
+          predictionAccumulatorRef.current = Math.min(
+            predictionAccumulatorRef.current + delta,
+            MAX_CATCH_UP_STEPS * SIMULATION_STEP
           );
-          localPositionRef.current.copy(predictedPosition);
+          while (predictionAccumulatorRef.current >= SIMULATION_STEP) {
+            predictionAccumulatorRef.current -= SIMULATION_STEP;
+            const predictedPosition = calculateClientMovement(
+              localPositionRef.current,
+              localRotationRef.current, // Pass current local rotation; function internally selects based on mode
+              currentInput,
+              SIMULATION_STEP
+            );
+            localPositionRef.current.copy(predictedPosition);
+            // Jump, gravity and landing on the terrain, same rules as the server
+            if (groundHeightAt) {
+              const ground = groundHeightAt(localPositionRef.current.x, localPositionRef.current.z);
+              const vertical = verticalRef.current;
+              const position = localPositionRef.current;
+              const jump = jumpRequestedRef.current;
+              jumpRequestedRef.current = false;
+              if (jump && !playerData.isDead && vertical.velocity <= 0 &&
+                  (vertical.isGrounded || vertical.airborneSeconds < COYOTE_TIME_SECONDS)) {
+                vertical.velocity = JUMP_VELOCITY;
+                vertical.isGrounded = false;
+                vertical.airborneSeconds = COYOTE_TIME_SECONDS;
+              }
+              if (vertical.isGrounded && vertical.velocity <= 0 && position.y - ground <= GROUND_SNAP_DISTANCE) {
+                position.y = ground;
+                vertical.velocity = 0;
+                vertical.airborneSeconds = 0;
+              } else {
+                vertical.velocity = Math.max(vertical.velocity - GRAVITY * SIMULATION_STEP, -TERMINAL_VELOCITY);
+                position.y = Math.max(position.y + vertical.velocity * SIMULATION_STEP, ground);
+                if (position.y <= ground && vertical.velocity <= 0) {
+                  vertical.velocity = 0;
+                  vertical.isGrounded = true;
+                  vertical.airborneSeconds = 0;
+                } else {
+                  vertical.isGrounded = false;
+                  vertical.airborneSeconds += SIMULATION_STEP;
+                }
+              }
+            }
+          }
 
           // 2. RECONCILIATION (Position)
           const serverPosition = new THREE.Vector3(dataRef.current.position.x, dataRef.current.position.y, dataRef.current.position.z);
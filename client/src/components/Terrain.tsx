@@ -0,0 +1,120 @@
+/**
+ * Terrain.tsx
+ *
+ * Renders the server heightmap (terrain_config / terrain_chunk tables) and samples
+ * ground height on the client.
+ *
+ * Key functionality:
+ * - One mesh per chunk, built from the chunk's (chunkSize + 1)^2 height samples
+ * - sampleTerrainHeight: Same bilinear interpolation as server/src/terrain.rs, used to keep
+ *   local prediction on the ground
+ *
+ * Props:
+ * - config: The terrain_config row (nothing is rendered until it arrives)
+ * - chunks: terrain_chunk rows keyed by chunkId
+ *
+ * Related files:
+ * - GameScene.tsx: Renders this component and passes the sampler to the local Player
+ * - server/src/terrain.rs: Grid layout and interpolation; keep both in sync
+ */
+
+import React, { useMemo } from 'react';
+import * as THREE from 'three';
+import { TerrainConfig, TerrainChunk } from '../generated';
+
+interface TerrainProps {
+  config: TerrainConfig | null;
+  chunks: ReadonlyMap<bigint, TerrainChunk>;
+}
+
+// Matches terrain::chunk_id on the server
+const chunkKey = (chunkX: number, chunkZ: number): bigint =>
+  (BigInt(chunkX >>> 0) << 32n) | BigInt(chunkZ >>> 0);
+
+// Ground height at world (x, z); mirrors Terrain::height_at in server/src/terrain.rs
+export const sampleTerrainHeight = (
+  config: TerrainConfig | null,
+  chunks: ReadonlyMap<bigint, TerrainChunk>,
+  x: number,
+  z: number,
+): number => {
+  if (!config) return 0;
+  const span = config.chunkSize;
+  const gridX = x / config.cellSize;
+  const gridZ = z / config.cellSize;
+  const chunkX = Math.floor(gridX / span);
+  const chunkZ = Math.floor(gridZ / span);
+  if (chunkX < -config.chunkRadius || chunkX >= config.chunkRadius ||
+      chunkZ < -config.chunkRadius || chunkZ >= config.chunkRadius) {
+    return config.defaultHeight;
+  }
+  const chunk = chunks.get(chunkKey(chunkX, chunkZ));
+  if (!chunk) return config.defaultHeight;
+
+  const samples = span + 1;
+  const localX = THREE.MathUtils.clamp(gridX - chunkX * span, 0, span);
+  const localZ = THREE.MathUtils.clamp(gridZ - chunkZ * span, 0, span);
+  const i0 = Math.min(Math.floor(localX), samples - 2);
+  const j0 = Math.min(Math.floor(localZ), samples - 2);
+  const fx = localX - i0;
+  const fz = localZ - j0;
+  const sample = (i: number, j: number) => chunk.heights[j * samples + i] ?? config.defaultHeight;
+
+  const near = sample(i0, j0) * (1 - fx) + sample(i0 + 1, j0) * fx;
+  const far = sample(i0, j0 + 1) * (1 - fx) + sample(i0 + 1, j0 + 1) * fx;
+  return near * (1 - fz) + far * fz;
+};
+
+const buildChunkGeometry = (config: TerrainConfig, chunk: TerrainChunk): THREE.BufferGeometry => {
+  const samples = config.chunkSize + 1;
+  const originX = chunk.chunkX * config.chunkSize * config.cellSize;
+  const originZ = chunk.chunkZ * config.chunkSize * config.cellSize;
+
+  const positions = new Float32Array(samples * samples * 3);
+  for (let j = 0; j < samples; j++) {
+    for (let i = 0; i < samples; i++) {
+      const index = j * samples + i;
+      positions[index * 3] = originX + i * config.cellSize;
+      positions[index * 3 + 1] = chunk.heights[index] ?? config.defaultHeight;
+      positions[index * 3 + 2] = originZ + j * config.cellSize;
+    }
+  }
+
+  // Two triangles per cell, wound counter-clockwise when seen from above
+  const indices: number[] = [];
+  for (let j = 0; j < samples - 1; j++) {
+    for (let i = 0; i < samples - 1; i++) {
+      const a = j * samples + i;
+      const b = a + 1;
+      const c = a + samples;
+      const d = c + 1;
+      indices.push(a, c, b, b, c, d);
+    }
+  }
+
+  const geometry = new THREE.BufferGeometry();
+  geometry.setAttribute('position', new THREE.BufferAttribute(positions, 3));
+  geometry.setIndex(indices);
+  geometry.computeVertexNormals();
+  return geometry;
+};
+
+const TerrainChunkMesh: React.FC<{ config: TerrainConfig; chunk: TerrainChunk }> = ({ config, chunk }) => {
+  const geometry = useMemo(() => buildChunkGeometry(config, chunk), [config, chunk]);
+  return (
+    <mesh geometry={geometry} receiveShadow>
+      <meshStandardMaterial color="#5f7a4f" flatShading />
+    </mesh>
+  );
+};
+
+export const Terrain: React.FC<TerrainProps> = ({ config, chunks }) => {
+  if (!config) return null;
+  return (
+    <group>
+      {Array.from(chunks.values()).map((chunk) => (
+        <TerrainChunkMesh key={chunk.chunkId.toString()} config={config} chunk={chunk} />
+      ))}
+    </group>
+  );
+};
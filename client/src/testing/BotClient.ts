@@ -70,7 +70,7 @@ export class BotClient {
                     this.conn = connection;
                     this.identity = id;
                     console.log(`Bot ${this.botId} connected with identity: ${id}`);
-                    this.registerPlayer();
+                    this.enterWorld();
                 })
                 .onDisconnect((_ctx: any, reason?: Error | null) => {
                     const reasonStr = reason ? reason.message : "No reason given";
@@ -86,15 +86,31 @@ export class BotClient {
         }
     }
 
-    private registerPlayer(): void {
+    private enterWorld(): void {
         if (!this.conn) return;
 
-        // Register the bot as a player
-        this.conn.reducers.registerPlayer(
-            `Bot_${this.botId.slice(0, 6)}`,
-            'Wizard', // Using Wizard as default character class
-            { r: Math.random(), g: Math.random(), b: Math.random() } // Random color
-        );
+        // Play as the first character this identity owns, creating one if needed
+        let entered = false;
+        this.conn.db.characterRoster.onInsert((_ctx: moduleBindings.EventContext, character: moduleBindings.Character) => {
+            if (!entered && this.conn && this.identity && character.owner.isEqual(this.identity)) {
+                entered = true;
+                this.conn.reducers.selectCharacter(character.characterId);
+            }
+        });
+        this.conn.subscriptionBuilder()
+            .onApplied(() => {
+                if (!this.conn || !this.identity) return;
+                const owned = [...this.conn.db.characterRoster.iter()]
+                    .filter((c) => this.identity !== null && c.owner.isEqual(this.identity));
+                if (owned.length === 0) {
+                    this.conn.reducers.createCharacter(
+                        `Bot_${this.botId.slice(0, 6)}`,
+                        'Wizard', // Using Wizard as default character class
+                        undefined // Let the server assign the least-used palette color
+                    );
+                }
+            })
+            .subscribe("SELECT * FROM character_roster");
     }
 
     setMovementPattern(pattern: MovementPattern): void {
@@ -208,8 +224,7 @@ export class BotClient {
             this.conn.reducers.updatePlayerInput(
                 this.inputState,
                 this.position,
-                this.rotation,
-                this.inputState.forward ? 'walk-forward' : 'idle'
+                this.rotation
             );
 
             // Update metrics
@@ -0,0 +1,349 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - admin.rs
+ *
+ * Operator roles and privileged reducers.
+ *
+ * Key components:
+ * - Role: Moderator (chat moderation) < Admin (everything, including granting roles)
+ * - StaffRole: Role per identity; the identity publishing the module is made Admin in init
+ * - Ban: Identities refused a connection and entry to the world, permanently or until expires_at
+ * - BanExpirySchedule: Scheduled table driving expire_bans
+ * - AdminAuditLog: One row per privileged action with the acting identity and time
+ * - kick / ban / teleport / heal / set_health / set_mana / reset: Player management
+ *
+ * When modifying:
+ * - Every privileged function must start with require_role and end with audit
+ * - Modules can't close client connections, so a kick removes the character from the world
+ *   (saving it like a disconnect) and a ban additionally stops it from re-entering
+ *
+ * Related files:
+ * - lib.rs: Admin reducers, calls init_admin; identity_connected rejects banned identities
+ * - moderation.rs: Chat moderation requires Role::Moderator or above
+ * - characters.rs: Refuses banned identities in enter_world
+ * - collision.rs: Admin-only obstacle editing uses require_role and audit
+ * - usernames.rs: Admin-only reserved name editing uses require_role and audit
+ */
+
+use spacetimedb::{Identity, ReducerContext, ScheduleAt, SpacetimeType, Table, Timestamp};
+use std::time::Duration;
+
+use crate::animation;
+use crate::bounds;
+use crate::classes;
+use crate::collision::CollisionWorld;
+use crate::common::{AnimationState, Vector3};
+use crate::player_logic;
+use crate::spawn;
+use crate::spells::{self, spell_cooldown};
+use crate::terrain::Terrain;
+use crate::{player, PlayerData};
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    Moderator,
+    Admin,
+}
+
+#[spacetimedb::table(name = staff_role)]
+#[derive(Clone)]
+pub struct StaffRole {
+    #[primary_key]
+    pub identity: Identity,
+    pub role: Role,
+    pub granted_by: Identity,
+    pub granted_at: Timestamp,
+}
+
+#[spacetimedb::table(name = ban)]
+#[derive(Clone)]
+pub struct Ban {
+    #[primary_key]
+    pub identity: Identity,
+    pub reason: String,
+    pub issued_by: Identity,
+    pub issued_at: Timestamp,
+    // None for permanent bans
+    pub expires_at: Option<Timestamp>,
+}
+
+#[spacetimedb::table(name = ban_expiry_schedule, scheduled(expire_bans))]
+pub struct BanExpirySchedule {
+    #[primary_key]
+    #[auto_inc]
+    scheduled_id: u64,
+    scheduled_at: ScheduleAt,
+}
+
+const BAN_EXPIRY_INTERVAL_SECONDS: u64 = 60;
+
+#[spacetimedb::table(name = admin_audit_log)]
+#[derive(Clone)]
+pub struct AdminAuditLog {
+    #[primary_key]
+    #[auto_inc]
+    pub log_id: u64,
+    pub actor: Identity,
+    pub action: String,
+    pub target: Option<Identity>,
+    pub details: String,
+    pub timestamp: Timestamp,
+}
+
+// Called from init: the identity publishing the module becomes the first admin, and
+// temporary ban expiry is scheduled
+pub fn init_admin(ctx: &ReducerContext) {
+    if ctx.db.ban_expiry_schedule().count() == 0 {
+        spacetimedb::log::info!("[INIT] Scheduling ban expiry (every {} seconds)...", BAN_EXPIRY_INTERVAL_SECONDS);
+        ctx.db.ban_expiry_schedule().insert(BanExpirySchedule {
+            scheduled_id: 0,
+            scheduled_at: ScheduleAt::Interval(Duration::from_secs(BAN_EXPIRY_INTERVAL_SECONDS).into()),
+        });
+    }
+
+    if ctx.db.staff_role().identity().find(ctx.sender).is_none() {
+        spacetimedb::log::info!("[INIT] Granting Admin to module owner {}", ctx.sender);
+        ctx.db.staff_role().insert(StaffRole {
+            identity: ctx.sender,
+            role: Role::Admin,
+            granted_by: ctx.sender,
+            granted_at: ctx.timestamp,
+        });
+    }
+}
+
+// Fails unless the caller holds at least `role`
+pub fn require_role(ctx: &ReducerContext, role: Role) -> Result<(), String> {
+    match ctx.db.staff_role().identity().find(ctx.sender) {
+        Some(staff) if staff.role >= role => Ok(()),
+        _ => Err("You don't have permission to do that".to_string()),
+    }
+}
+
+pub fn audit(ctx: &ReducerContext, action: &str, target: Option<Identity>, details: String) {
+    spacetimedb::log::info!("Admin: {} by {} (target {:?}): {}", action, ctx.sender, target, details);
+    ctx.db.admin_audit_log().insert(AdminAuditLog {
+        log_id: 0,
+        actor: ctx.sender,
+        action: action.to_string(),
+        target,
+        details,
+        timestamp: ctx.timestamp,
+    });
+}
+
+// The ban currently in effect for `identity`, if any (expired bans awaiting cleanup are ignored)
+pub fn active_ban(ctx: &ReducerContext, identity: Identity) -> Option<Ban> {
+    ctx.db.ban()
+        .identity()
+        .find(identity)
+        .filter(|ban| ban.expires_at.map_or(true, |expires_at| expires_at > ctx.timestamp))
+}
+
+// Message shown to a banned identity
+pub fn ban_message(ctx: &ReducerContext, ban: &Ban) -> String {
+    match ban.expires_at {
+        Some(expires_at) => {
+            let remaining_minutes = expires_at
+                .duration_since(ctx.timestamp)
+                .map(|d| d.as_secs().div_ceil(60))
+                .unwrap_or(0);
+            format!("You are banned for another {} minutes: {}", remaining_minutes, ban.reason)
+        }
+        None => format!("You are banned from this server: {}", ban.reason),
+    }
+}
+
+#[spacetimedb::reducer]
+pub fn expire_bans(ctx: &ReducerContext, _schedule: BanExpirySchedule) -> Result<(), String> {
+    // Only the scheduler may expire bans
+    if ctx.sender != ctx.identity() {
+        return Err("expire_bans may only be invoked by the scheduler".to_string());
+    }
+
+    let expired: Vec<Ban> = ctx.db.ban()
+        .iter()
+        .filter(|ban| ban.expires_at.is_some_and(|expires_at| expires_at <= ctx.timestamp))
+        .collect();
+    for ban in expired {
+        spacetimedb::log::info!("Ban on {} expired.", ban.identity);
+        ctx.db.ban().delete(ban);
+    }
+    Ok(())
+}
+
+pub fn grant_role(ctx: &ReducerContext, target: Identity, role: Role) -> Result<(), String> {
+    require_role(ctx, Role::Admin)?;
+    let staff = StaffRole { identity: target, role, granted_by: ctx.sender, granted_at: ctx.timestamp };
+    if ctx.db.staff_role().identity().find(target).is_some() {
+        ctx.db.staff_role().identity().update(staff);
+    } else {
+        ctx.db.staff_role().insert(staff);
+    }
+    audit(ctx, "grant_role", Some(target), format!("{:?}", role));
+    Ok(())
+}
+
+pub fn revoke_role(ctx: &ReducerContext, target: Identity) -> Result<(), String> {
+    require_role(ctx, Role::Admin)?;
+    if target == ctx.sender {
+        return Err("You can't revoke your own role".to_string());
+    }
+    if !ctx.db.staff_role().identity().delete(target) {
+        return Err("That identity has no role".to_string());
+    }
+    audit(ctx, "revoke_role", Some(target), String::new());
+    Ok(())
+}
+
+pub fn kick(ctx: &ReducerContext, target: Identity, reason: String) -> Result<(), String> {
+    require_role(ctx, Role::Admin)?;
+    let player = find_player(ctx, target)?;
+    crate::move_to_logged_out(ctx, player, ctx.timestamp);
+    audit(ctx, "kick", Some(target), reason);
+    Ok(())
+}
+
+// Bans `target` for `duration_seconds`, or permanently when None
+pub fn ban(ctx: &ReducerContext, target: Identity, reason: String, duration_seconds: Option<u32>) -> Result<(), String> {
+    require_role(ctx, Role::Admin)?;
+    if target == ctx.sender {
+        return Err("You can't ban yourself".to_string());
+    }
+    let expires_at = match duration_seconds {
+        Some(0) => return Err("Ban duration must be at least one second".to_string()),
+        Some(seconds) => Some(Timestamp::from_micros_since_unix_epoch(
+            ctx.timestamp.to_micros_since_unix_epoch() + seconds as i64 * 1_000_000,
+        )),
+        None => None,
+    };
+    let entry = Ban {
+        identity: target,
+        reason: reason.clone(),
+        issued_by: ctx.sender,
+        issued_at: ctx.timestamp,
+        expires_at,
+    };
+    if ctx.db.ban().identity().find(target).is_some() {
+        ctx.db.ban().identity().update(entry);
+    } else {
+        ctx.db.ban().insert(entry);
+    }
+    if let Some(player) = ctx.db.player().identity().find(target) {
+        crate::move_to_logged_out(ctx, player, ctx.timestamp);
+    }
+    let duration = duration_seconds.map_or("permanent".to_string(), |s| format!("{}s", s));
+    audit(ctx, "ban", Some(target), format!("{}: {}", duration, reason));
+    Ok(())
+}
+
+pub fn unban(ctx: &ReducerContext, target: Identity) -> Result<(), String> {
+    require_role(ctx, Role::Admin)?;
+    if !ctx.db.ban().identity().delete(target) {
+        return Err("That identity is not banned".to_string());
+    }
+    audit(ctx, "unban", Some(target), String::new());
+    Ok(())
+}
+
+pub fn teleport(ctx: &ReducerContext, target: Identity, position: Vector3) -> Result<(), String> {
+    require_role(ctx, Role::Admin)?;
+    bounds::require_finite(&position, "position")?;
+    if !bounds::world_bounds(ctx).contains(&position) {
+        return Err("Position is outside the world bounds".to_string());
+    }
+    let mut player = find_player(ctx, target)?;
+    // Teleporting into a wall would leave the player stuck inside it
+    let position = Terrain::new(ctx).snap_to_ground(&CollisionWorld::new(ctx).slide(&position, &position));
+    let details = format!("{:?} -> {:?}", player.position, position);
+    player.position = position;
+    player_logic::reset_vertical(&mut player);
+    player.last_update = ctx.timestamp;
+    spells::interrupt_cast(ctx, &mut player, "teleported");
+    save_player(ctx, player);
+    audit(ctx, "teleport", Some(target), details);
+    Ok(())
+}
+
+pub fn heal(ctx: &ReducerContext, target: Identity) -> Result<(), String> {
+    require_role(ctx, Role::Admin)?;
+    let mut player = find_player(ctx, target)?;
+    player.health = player.max_health;
+    player.mana = player.max_mana;
+    revive(ctx, &mut player);
+    save_player(ctx, player);
+    audit(ctx, "heal", Some(target), String::new());
+    Ok(())
+}
+
+pub fn set_health(ctx: &ReducerContext, target: Identity, health: i32) -> Result<(), String> {
+    require_role(ctx, Role::Admin)?;
+    let mut player = find_player(ctx, target)?;
+    let details = format!("{} -> {}", player.health, health);
+    if health <= 0 {
+        if !player.is_dead {
+            spawn::kill_player(ctx, &mut player);
+        }
+    } else {
+        player.health = health.min(player.max_health);
+        revive(ctx, &mut player);
+    }
+    save_player(ctx, player);
+    audit(ctx, "set_health", Some(target), details);
+    Ok(())
+}
+
+pub fn set_mana(ctx: &ReducerContext, target: Identity, mana: i32) -> Result<(), String> {
+    require_role(ctx, Role::Admin)?;
+    let mut player = find_player(ctx, target)?;
+    let details = format!("{} -> {}", player.mana, mana);
+    player.mana = mana.clamp(0, player.max_mana);
+    save_player(ctx, player);
+    audit(ctx, "set_mana", Some(target), details);
+    Ok(())
+}
+
+// Restores class base stats, clears cooldowns and casts and moves the player to a spawn point
+pub fn reset(ctx: &ReducerContext, target: Identity) -> Result<(), String> {
+    require_role(ctx, Role::Admin)?;
+    let mut player = find_player(ctx, target)?;
+    let class = classes::class_stats(ctx, &player.character_class);
+    spells::interrupt_cast(ctx, &mut player, "reset");
+    let cooldowns: Vec<_> = ctx.db.spell_cooldown().caster().filter(&target).collect();
+    for cooldown in cooldowns {
+        ctx.db.spell_cooldown().delete(cooldown);
+    }
+
+    let (position, rotation) = spawn::select_spawn_point(ctx, target, player.team);
+    player.position = position;
+    player.rotation = rotation;
+    player_logic::reset_vertical(&mut player);
+    player.max_health = class.base_health;
+    player.health = class.base_health;
+    player.max_mana = class.base_mana;
+    player.mana = class.base_mana;
+    player.last_update = ctx.timestamp;
+    player.last_attack_at = Timestamp::UNIX_EPOCH;
+    player.is_dead = false;
+    animation::reset_animation(&mut player, AnimationState::Idle, ctx.timestamp);
+    save_player(ctx, player);
+    audit(ctx, "reset", Some(target), String::new());
+    Ok(())
+}
+
+fn find_player(ctx: &ReducerContext, target: Identity) -> Result<PlayerData, String> {
+    ctx.db.player().identity().find(target)
+        .ok_or_else(|| "That player is not in the world".to_string())
+}
+
+fn revive(ctx: &ReducerContext, player: &mut PlayerData) {
+    if player.is_dead {
+        player.is_dead = false;
+        animation::reset_animation(player, AnimationState::Idle, ctx.timestamp);
+    }
+}
+
+// Writes the row and republishes the ack so the client reconciles with the new state
+fn save_player(ctx: &ReducerContext, player: PlayerData) {
+    let player = ctx.db.player().identity().update(player);
+    crate::publish_input_ack(ctx, &player);
+}
@@ -0,0 +1,229 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - animation.rs
+ *
+ * Server-side animation state machine. Every client renders the state stored
+ * on PlayerData, so animations are consistent and can't be spoofed by a client.
+ *
+ * Key components:
+ * - play_action: Starts a one-shot action (attack, cast, hurt, dead) if allowed to interrupt
+ * - play_action_for: Same, held for a given duration (casts last their spell's cast time)
+ * - update_animation: Derives locomotion (idle/walk/run, or jump while airborne) once actions have finished
+ * - animation_name: Maps a state plus input direction to the client's animation clip names
+ *
+ * Transition rules:
+ * - One-shot states (jump, attack, cast, hurt) hold for a duration stored in animation_duration:
+ *   the state's default below, or the spell's cast time for casts
+ * - A higher priority state may interrupt a lower one (dead > hurt > attack/cast > jump > locomotion)
+ * - Dead is sticky until reset_animation is called (e.g. on respawn)
+ *
+ * Related files:
+ * - common.rs: AnimationState enum
+ * - player_logic.rs: Calls update_animation on input and every tick
+ * - client/src/components/Player.tsx: Animation clip names
+ */
+
+use spacetimedb::Timestamp;
+
+use crate::common::{AnimationState, InputState};
+use crate::PlayerData;
+
+// Default one-shot animation durations in seconds
+const JUMP_DURATION: f32 = 0.8;
+const ATTACK_DURATION: f32 = 0.6;
+const CAST_DURATION: f32 = 1.0;
+const HURT_DURATION: f32 = 0.4;
+
+fn one_shot_duration(state: AnimationState) -> Option<f32> {
+    match state {
+        AnimationState::Jump => Some(JUMP_DURATION),
+        AnimationState::Attack => Some(ATTACK_DURATION),
+        AnimationState::Cast => Some(CAST_DURATION),
+        AnimationState::Hurt => Some(HURT_DURATION),
+        _ => None,
+    }
+}
+
+fn default_duration(state: AnimationState) -> f32 {
+    one_shot_duration(state).unwrap_or(0.0)
+}
+
+fn priority(state: AnimationState) -> u8 {
+    match state {
+        AnimationState::Dead => 4,
+        AnimationState::Hurt => 3,
+        AnimationState::Attack | AnimationState::Cast => 2,
+        AnimationState::Jump => 1,
+        AnimationState::Idle | AnimationState::Walk | AnimationState::Run => 0,
+    }
+}
+
+// Whether the current state may be replaced by any other state
+fn is_finished(player: &PlayerData, now: Timestamp) -> bool {
+    match one_shot_duration(player.animation_state) {
+        Some(_) => {
+            let elapsed = now
+                .duration_since(player.animation_started_at)
+                .map(|d| d.as_secs_f32())
+                .unwrap_or(0.0);
+            elapsed >= player.animation_duration
+        }
+        None => player.animation_state != AnimationState::Dead,
+    }
+}
+
+fn set_state(player: &mut PlayerData, state: AnimationState, duration: f32, now: Timestamp) {
+    player.animation_state = state;
+    player.animation_started_at = now;
+    player.animation_duration = duration;
+    player.is_attacking = state == AnimationState::Attack;
+    player.is_casting = state == AnimationState::Cast;
+}
+
+// Starts an action state if it may interrupt the current one. Returns true if it started.
+pub fn play_action(player: &mut PlayerData, state: AnimationState, now: Timestamp) -> bool {
+    play_action_for(player, state, default_duration(state), now)
+}
+
+// Like play_action, but holds the state for `duration` seconds instead of its default
+pub fn play_action_for(player: &mut PlayerData, state: AnimationState, duration: f32, now: Timestamp) -> bool {
+    if !is_finished(player, now) && priority(state) <= priority(player.animation_state) {
+        return false;
+    }
+    set_state(player, state, duration, now);
+    player.current_animation = animation_name(state, &player.input);
+    true
+}
+
+// Forces a state regardless of priority (e.g. leaving Dead on respawn)
+pub fn reset_animation(player: &mut PlayerData, state: AnimationState, now: Timestamp) {
+    set_state(player, state, default_duration(state), now);
+    player.current_animation = animation_name(state, &player.input);
+}
+
+// Derives locomotion from stored input once any action has finished.
+// Returns true if the player's animation changed.
+pub fn update_animation(player: &mut PlayerData, now: Timestamp) -> bool {
+    if !is_finished(player, now) {
+        return false;
+    }
+
+    let desired = if !player.is_grounded {
+        AnimationState::Jump
+    } else if player.is_running {
+        AnimationState::Run
+    } else if player.is_moving {
+        AnimationState::Walk
+    } else {
+        AnimationState::Idle
+    };
+
+    let mut changed = false;
+    if desired != player.animation_state || one_shot_duration(desired).is_some() {
+        set_state(player, desired, default_duration(desired), now);
+        changed = true;
+    }
+
+    let name = animation_name(desired, &player.input);
+    if name != player.current_animation {
+        player.current_animation = name;
+        changed = true;
+    }
+    changed
+}
+
+// Client animation clip name for a state; locomotion uses the input direction
+pub fn animation_name(state: AnimationState, input: &InputState) -> String {
+    match state {
+        AnimationState::Idle => "idle".to_string(),
+        AnimationState::Jump => "jump".to_string(),
+        AnimationState::Attack => "attack1".to_string(),
+        AnimationState::Cast => "cast".to_string(),
+        AnimationState::Hurt => "damage".to_string(),
+        AnimationState::Dead => "death".to_string(),
+        AnimationState::Walk => format!("walk-{}", movement_direction(input)),
+        AnimationState::Run => format!("run-{}", movement_direction(input)),
+    }
+}
+
+// Dominant movement direction, matching the client's previous determineAnimation logic
+fn movement_direction(input: &InputState) -> &'static str {
+    if input.forward && !input.backward {
+        "forward"
+    } else if input.backward && !input.forward {
+        "back"
+    } else if input.left && !input.right {
+        "left"
+    } else if input.right && !input.left {
+        "right"
+    } else {
+        "forward"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seconds(s: f32) -> Timestamp {
+        Timestamp::from_micros_since_unix_epoch((s * 1_000_000.0) as i64)
+    }
+
+    #[test]
+    fn higher_priority_interrupts_unfinished_actions() {
+        let mut player = crate::test_player();
+        assert!(play_action(&mut player, AnimationState::Attack, seconds(0.0)));
+        assert!(player.is_attacking);
+        assert!(!play_action(&mut player, AnimationState::Jump, seconds(0.1)));
+        assert!(play_action(&mut player, AnimationState::Hurt, seconds(0.1)));
+        assert_eq!(player.animation_state, AnimationState::Hurt);
+        assert!(!player.is_attacking);
+    }
+
+    #[test]
+    fn cast_holds_for_the_given_duration() {
+        let mut player = crate::test_player();
+        assert!(play_action_for(&mut player, AnimationState::Cast, 2.5, seconds(0.0)));
+        assert!(!update_animation(&mut player, seconds(2.0)));
+        assert_eq!(player.animation_state, AnimationState::Cast);
+        assert!(update_animation(&mut player, seconds(2.5)));
+        assert_eq!(player.animation_state, AnimationState::Idle);
+        assert!(!player.is_casting);
+    }
+
+    #[test]
+    fn dead_is_sticky_until_reset() {
+        let mut player = crate::test_player();
+        assert!(play_action(&mut player, AnimationState::Dead, seconds(0.0)));
+        assert!(!update_animation(&mut player, seconds(60.0)));
+        assert!(!play_action(&mut player, AnimationState::Hurt, seconds(60.0)));
+        reset_animation(&mut player, AnimationState::Idle, seconds(61.0));
+        assert_eq!(player.animation_state, AnimationState::Idle);
+    }
+
+    #[test]
+    fn locomotion_follows_movement_flags() {
+        let mut player = crate::test_player();
+        player.is_moving = true;
+        player.is_running = true;
+        player.input.left = true;
+        assert!(update_animation(&mut player, seconds(0.0)));
+        assert_eq!(player.animation_state, AnimationState::Run);
+        assert_eq!(player.current_animation, "run-left");
+
+        player.is_grounded = false;
+        assert!(update_animation(&mut player, seconds(0.1)));
+        assert_eq!(player.animation_state, AnimationState::Jump);
+    }
+
+    #[test]
+    fn clip_names_use_the_dominant_direction() {
+        let mut input = crate::test_player().input;
+        assert_eq!(animation_name(AnimationState::Walk, &input), "walk-forward");
+        input.backward = true;
+        assert_eq!(animation_name(AnimationState::Walk, &input), "walk-back");
+        input.forward = true;
+        input.right = true;
+        assert_eq!(animation_name(AnimationState::Run, &input), "run-right");
+        assert_eq!(animation_name(AnimationState::Hurt, &input), "damage");
+    }
+}
@@ -0,0 +1,213 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - bounds.rs
+ *
+ * World bounds, rejection of malformed vectors and out-of-bounds recovery.
+ *
+ * Key components:
+ * - WorldBounds: Public singleton with the inclusive min/max corner of the playable world
+ * - OutOfBoundsEvent: Log of every player returned to a spawn point, with where they were found
+ * - require_finite: Rejects NaN/infinite vectors; every Vector3 a reducer receives goes through it
+ * - recover_player: Moves a player back to the nearest spawn point and logs the event
+ *
+ * Enforcement:
+ * - Movement treats the horizontal bounds as walls (CollisionWorld::slide clamps to them)
+ * - update_player_input and game_tick recover any player found outside the bounds, which
+ *   also catches rows that were corrupted before this check existed
+ * - admin_teleport refuses destinations outside the bounds
+ *
+ * When modifying:
+ * - The default bounds match the terrain heightmap; outside it the ground is flat default_height
+ * - Bounds can be changed at runtime with admin_set_world_bounds; every spawn point must stay
+ *   inside them, otherwise recovered players would be recovered again on the next tick
+ *
+ * Related files:
+ * - lib.rs: Input validation in reducers, admin_set_world_bounds, calls init_world_bounds
+ * - player_logic.rs: Recovers out-of-bounds players every tick
+ * - collision.rs: Clamps movement to the horizontal bounds
+ * - spawn.rs: nearest_spawn_point picks the recovery destination
+ * - terrain.rs: terrain_half_extent sizes the default bounds
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+
+use crate::admin::{self, Role};
+use crate::common::Vector3;
+use crate::player_logic;
+use crate::spawn::{self, spawn_point};
+use crate::spells;
+use crate::terrain;
+use crate::PlayerData;
+
+// Vertical range of the default bounds; X/Z come from the terrain extent
+const DEFAULT_MIN_Y: f32 = -50.0;
+const DEFAULT_MAX_Y: f32 = 200.0;
+// Smallest allowed extent on each axis, so the world always has room to move
+const MIN_EXTENT: f32 = 10.0;
+
+#[spacetimedb::table(name = world_bounds, public)]
+#[derive(Clone)]
+pub struct WorldBounds {
+    #[primary_key]
+    pub id: u32,
+    pub min: Vector3,
+    pub max: Vector3,
+}
+
+#[spacetimedb::table(name = out_of_bounds_event)]
+#[derive(Clone)]
+pub struct OutOfBoundsEvent {
+    #[primary_key]
+    #[auto_inc]
+    pub event_id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub character_id: u64,
+    // Where the player was found; may contain NaN/infinite components
+    pub position: Vector3,
+    pub recovered_to: Vector3,
+    pub reason: String,
+    pub timestamp: Timestamp,
+}
+
+impl WorldBounds {
+    // True if every component is finite and within the bounds
+    pub fn contains(&self, position: &Vector3) -> bool {
+        position.x >= self.min.x && position.x <= self.max.x
+            && position.y >= self.min.y && position.y <= self.max.y
+            && position.z >= self.min.z && position.z <= self.max.z
+    }
+
+    // Clamps X/Z so a circle of `margin` around the position stays inside the bounds
+    pub fn clamp_horizontal(&self, position: &Vector3, margin: f32) -> Vector3 {
+        Vector3 {
+            x: position.x.clamp(self.min.x + margin, self.max.x - margin),
+            y: position.y,
+            z: position.z.clamp(self.min.z + margin, self.max.z - margin),
+        }
+    }
+}
+
+// Default bounds: the heightmap's horizontal extent, so players can't walk off the terrain
+fn default_bounds(ctx: &ReducerContext) -> WorldBounds {
+    let extent = terrain::terrain_half_extent(ctx);
+    WorldBounds {
+        id: 0,
+        min: Vector3 { x: -extent, y: DEFAULT_MIN_Y, z: -extent },
+        max: Vector3 { x: extent, y: DEFAULT_MAX_Y, z: extent },
+    }
+}
+
+// Called from init (after init_terrain): creates the default bounds
+pub fn init_world_bounds(ctx: &ReducerContext) {
+    if ctx.db.world_bounds().id().find(0).is_none() {
+        ctx.db.world_bounds().insert(default_bounds(ctx));
+    }
+}
+
+pub fn world_bounds(ctx: &ReducerContext) -> WorldBounds {
+    ctx.db.world_bounds()
+        .id()
+        .find(0)
+        .unwrap_or_else(|| default_bounds(ctx))
+}
+
+// Fails if `value` has a NaN or infinite component; `name` is used in the error
+pub fn require_finite(value: &Vector3, name: &str) -> Result<(), String> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(format!("Invalid {}: components must be finite numbers", name))
+    }
+}
+
+// Returns a player that left the world bounds to the nearest spawn point they may use
+pub fn recover_player(ctx: &ReducerContext, player: &mut PlayerData, reason: &str) {
+    let found_at = player.position.clone();
+    let (position, rotation) = spawn::nearest_spawn_point(ctx, player.identity, &found_at, player.team);
+    spacetimedb::log::warn!(
+        "Player {} out of bounds at {:?} ({}), returning to {:?}",
+        player.identity,
+        found_at,
+        reason,
+        position
+    );
+    ctx.db.out_of_bounds_event().insert(OutOfBoundsEvent {
+        event_id: 0,
+        identity: player.identity,
+        character_id: player.character_id,
+        position: found_at,
+        recovered_to: position.clone(),
+        reason: reason.to_string(),
+        timestamp: ctx.timestamp,
+    });
+
+    player.position = position;
+    player.rotation = rotation;
+    player_logic::reset_vertical(player);
+    player.last_update = ctx.timestamp;
+    spells::interrupt_cast(ctx, player, "returned to spawn");
+}
+
+pub fn set_world_bounds(ctx: &ReducerContext, min: Vector3, max: Vector3) -> Result<(), String> {
+    admin::require_role(ctx, Role::Admin)?;
+    require_finite(&min, "min")?;
+    require_finite(&max, "max")?;
+    if max.x - min.x < MIN_EXTENT || max.y - min.y < MIN_EXTENT || max.z - min.z < MIN_EXTENT {
+        return Err(format!("Bounds must span at least {} units on every axis", MIN_EXTENT));
+    }
+    let bounds = WorldBounds { id: 0, min, max };
+    if let Some(outside) = ctx.db.spawn_point().iter().find(|p| !bounds.contains(&p.position)) {
+        return Err(format!("Spawn point {} would be outside the bounds", outside.spawn_id));
+    }
+
+    let details = format!("{:?} .. {:?}", bounds.min, bounds.max);
+    if ctx.db.world_bounds().id().find(0).is_some() {
+        ctx.db.world_bounds().id().update(bounds);
+    } else {
+        ctx.db.world_bounds().insert(bounds);
+    }
+    admin::audit(ctx, "set_world_bounds", None, details);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds() -> WorldBounds {
+        WorldBounds {
+            id: 0,
+            min: Vector3 { x: -128.0, y: DEFAULT_MIN_Y, z: -128.0 },
+            max: Vector3 { x: 128.0, y: DEFAULT_MAX_Y, z: 128.0 },
+        }
+    }
+
+    #[test]
+    fn bounds_are_inclusive() {
+        assert!(bounds().contains(&Vector3 { x: 0.0, y: 0.0, z: 0.0 }));
+        assert!(bounds().contains(&Vector3 { x: 128.0, y: DEFAULT_MAX_Y, z: -128.0 }));
+        assert!(!bounds().contains(&Vector3 { x: 128.1, y: 0.0, z: 0.0 }));
+        assert!(!bounds().contains(&Vector3 { x: 0.0, y: DEFAULT_MIN_Y - 1.0, z: 0.0 }));
+    }
+
+    #[test]
+    fn non_finite_positions_are_outside() {
+        assert!(!bounds().contains(&Vector3 { x: f32::NAN, y: 0.0, z: 0.0 }));
+        assert!(!bounds().contains(&Vector3 { x: 0.0, y: f32::INFINITY, z: 0.0 }));
+    }
+
+    #[test]
+    fn clamping_keeps_the_margin_and_height() {
+        let clamped = bounds().clamp_horizontal(&Vector3 { x: 200.0, y: 7.0, z: -130.0 }, 0.5);
+        assert_eq!(clamped, Vector3 { x: 127.5, y: 7.0, z: -127.5 });
+        let inside = Vector3 { x: 10.0, y: 1.0, z: -10.0 };
+        assert_eq!(bounds().clamp_horizontal(&inside, 0.5), inside);
+    }
+
+    #[test]
+    fn require_finite_rejects_nan_and_infinity() {
+        assert!(require_finite(&Vector3 { x: 1.0, y: 2.0, z: 3.0 }, "position").is_ok());
+        assert!(require_finite(&Vector3 { x: f32::NAN, y: 0.0, z: 0.0 }, "position").is_err());
+        assert!(require_finite(&Vector3 { x: 0.0, y: 0.0, z: f32::NEG_INFINITY }, "position").is_err());
+    }
+}
@@ -0,0 +1,219 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - characters.rs
+ *
+ * Character roster: each identity can own several characters and picks one to play.
+ *
+ * Key components:
+ * - Character: Public roster row (owner, name, class, color) for every character
+ * - create_character / delete_character: Roster management for the caller
+ * - enter_world: Loads a character into the player table, restoring its saved state
+ *
+ * Lifecycle:
+ * - A new character has no logged_out_player row and enters at a spawn point with its class stats
+ * - While playing, the character's state lives in `player` (PlayerData.character_id)
+ * - On disconnect or character switch it is saved to logged_out_player, keyed by character_id
+ * - Character.last_seen is refreshed on creation, entry and exit; retention.rs purges by it
+ *
+ * When modifying:
+ * - Only one character per identity can be in the world at a time (player is keyed by Identity)
+ * - Switching characters is refused while dead or during the melee cooldown
+ * - MAX_CHARACTERS_PER_ACCOUNT caps the roster size
+ *
+ * Related files:
+ * - lib.rs: create_character / delete_character / select_character reducers, move_to_logged_out
+ * - usernames.rs: Character name validation
+ * - spawn.rs: Spawn selection and saved position restoration
+ * - admin.rs: Banned identities can't enter the world
+ * - validation.rs: Identities kicked for movement violations can't enter until the cooldown ends
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+
+use crate::admin;
+use crate::classes;
+use crate::combat;
+use crate::common::{AnimationState, InputState, Vector3};
+use crate::palette;
+use crate::profiles::{self, player_profile};
+use crate::spawn;
+use crate::usernames;
+use crate::validation;
+use crate::{logged_out_player, player, PlayerData};
+
+pub const MAX_CHARACTERS_PER_ACCOUNT: usize = 5;
+
+#[spacetimedb::table(name = character_roster, public)]
+#[derive(Clone)]
+pub struct Character {
+    #[primary_key]
+    #[auto_inc]
+    pub character_id: u64,
+    #[index(btree)]
+    pub owner: Identity,
+    pub name: String,
+    pub character_class: String,
+    pub color: String,
+    pub created_at: Timestamp,
+    pub last_played: Timestamp,
+    // Last time the character was created, entered or left the world (drives retention)
+    pub last_seen: Timestamp,
+}
+
+// Creates a character for the caller. Without a color, the profile color (or the
+// least-used palette color for a first character) is used.
+pub fn create_character(
+    ctx: &ReducerContext,
+    name: String,
+    character_class: String,
+    color: Option<String>,
+) -> Result<Character, String> {
+    let owner = ctx.sender;
+    let existing: Vec<Character> = ctx.db.character_roster().owner().filter(&owner).collect();
+    if existing.len() >= MAX_CHARACTERS_PER_ACCOUNT {
+        return Err(format!("You can have at most {} characters", MAX_CHARACTERS_PER_ACCOUNT));
+    }
+
+    let name = usernames::validate_username(ctx, owner, &name)?;
+    if existing.iter().any(|c| c.name.to_lowercase() == name.to_lowercase()) {
+        return Err(format!("You already have a character named '{}'", name));
+    }
+    let class = classes::find_class(ctx, &character_class)
+        .ok_or_else(|| format!("Unknown character class '{}'", character_class))?;
+    let color = match color {
+        Some(color) => palette::resolve_color(ctx, &color)?,
+        None => match ctx.db.player_profile().identity().find(owner) {
+            Some(profile) => profile.color,
+            None => palette::least_used_color(ctx),
+        },
+    };
+
+    // The first character's name and color become the account profile
+    profiles::ensure_profile(ctx, owner, &name, &color);
+    spacetimedb::log::info!("Player {} created character {} ({})", owner, name, class.class_name);
+    Ok(ctx.db.character_roster().insert(Character {
+        character_id: 0,
+        owner,
+        name,
+        character_class: class.class_name,
+        color,
+        created_at: ctx.timestamp,
+        last_played: Timestamp::UNIX_EPOCH,
+        last_seen: ctx.timestamp,
+    }))
+}
+
+// Deletes one of the caller's characters and its saved state
+pub fn delete_character(ctx: &ReducerContext, character_id: u64) -> Result<(), String> {
+    let character = owned_character(ctx, character_id)?;
+    let active = ctx.db.player()
+        .identity()
+        .find(ctx.sender)
+        .is_some_and(|p| p.character_id == character_id);
+    if active {
+        return Err("You can't delete the character you are playing".to_string());
+    }
+
+    ctx.db.logged_out_player().character_id().delete(character_id);
+    ctx.db.character_roster().character_id().delete(character_id);
+    spacetimedb::log::info!("Player {} deleted character {}", ctx.sender, character.name);
+    Ok(())
+}
+
+// Puts the caller's character into the world, saving whichever character they were playing
+pub fn enter_world(ctx: &ReducerContext, character_id: u64) -> Result<(), String> {
+    let identity = ctx.sender;
+    if let Some(ban) = admin::active_ban(ctx, identity) {
+        return Err(admin::ban_message(ctx, &ban));
+    }
+    if let Some(remaining) = validation::kick_cooldown_remaining(ctx, identity) {
+        return Err(format!("You were kicked for invalid movement; try again in {} seconds", remaining));
+    }
+    let mut character = owned_character(ctx, character_id)?;
+
+    if let Some(current) = ctx.db.player().identity().find(identity) {
+        if current.character_id == character_id {
+            return Err("You are already playing this character".to_string());
+        }
+        // Switching would otherwise skip the respawn timer or reset the attack cooldown
+        if current.is_dead {
+            return Err("You can't switch characters while dead".to_string());
+        }
+        if combat::in_attack_cooldown(ctx, &current) {
+            return Err("You can't switch characters right after attacking".to_string());
+        }
+        crate::move_to_logged_out(ctx, current, ctx.timestamp);
+    }
+
+    let team = spawn::assign_team(ctx);
+    let (position, rotation, health, max_health, mana, max_mana) =
+        match ctx.db.logged_out_player().character_id().find(character_id) {
+            Some(saved) => {
+                spacetimedb::log::info!("Player {} is rejoining as {}.", identity, character.name);
+                let (position, rotation) = spawn::restore_position(ctx, &saved, team);
+                ctx.db.logged_out_player().character_id().delete(character_id);
+                // Characters that logged out while dead come back alive
+                let health = if saved.health > 0 { saved.health } else { saved.max_health };
+                (position, rotation, health, saved.max_health, saved.mana, saved.max_mana)
+            }
+            None => {
+                spacetimedb::log::info!("Player {} is entering the world as {} for the first time.", identity, character.name);
+                let class = classes::class_stats(ctx, &character.character_class);
+                let (position, rotation) = spawn::select_spawn_point(ctx, identity, team);
+                (position, rotation, class.base_health, class.base_health, class.base_mana, class.base_mana)
+            }
+        };
+
+    let default_input = InputState {
+        forward: false, backward: false, left: false, right: false,
+        sprint: false, jump: false, attack: false, cast_spell: false,
+        sequence: 0
+    };
+    let player = PlayerData {
+        identity,
+        character_id,
+        username: character.name.clone(),
+        character_class: character.character_class.clone(),
+        position,
+        rotation,
+        health,
+        max_health,
+        mana,
+        max_mana,
+        current_animation: "idle".to_string(),
+        animation_state: AnimationState::Idle,
+        animation_started_at: ctx.timestamp,
+        animation_duration: 0.0,
+        is_moving: false,
+        is_running: false,
+        is_grounded: true,
+        vertical_velocity: 0.0,
+        airborne_seconds: 0.0,
+        jump_requested: false,
+        hint_offset: Vector3 { x: 0.0, y: 0.0, z: 0.0 },
+        is_attacking: false,
+        is_casting: false,
+        last_input_seq: 0,
+        input: default_input,
+        color: character.color.clone(),
+        last_update: ctx.timestamp,
+        last_attack_at: Timestamp::UNIX_EPOCH,
+        is_dead: false,
+        respawn_at: Timestamp::UNIX_EPOCH,
+        team,
+    };
+
+    let player = ctx.db.player().insert(player);
+    character.last_played = ctx.timestamp;
+    character.last_seen = ctx.timestamp;
+    ctx.db.character_roster().character_id().update(character);
+    crate::publish_input_ack(ctx, &player);
+    Ok(())
+}
+
+fn owned_character(ctx: &ReducerContext, character_id: u64) -> Result<Character, String> {
+    ctx.db.character_roster()
+        .character_id()
+        .find(character_id)
+        .filter(|c| c.owner == ctx.sender)
+        .ok_or_else(|| "Character not found".to_string())
+}
@@ -0,0 +1,226 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - chat.rs
+ *
+ * Text chat between players in the world.
+ *
+ * Key components:
+ * - ChatChannel: Global, Proximity (players within PROXIMITY_CHAT_RADIUS) and Whisper
+ * - ChatMessage: Public message table; `recipient` is None for global messages
+ * - ChatRateLimit: Per-identity message counter for the current rate limit window (expired
+ *   rows are deleted by rate_limit::sweep_rate_limits)
+ * - ChatPruneSchedule: Scheduled table driving prune_chat_messages
+ *
+ * Delivery:
+ * - Global messages are stored once with no recipient
+ * - Proximity messages are stored once per player in range (including the sender)
+ * - Whispers are stored once, addressed to the target; clients show rows they sent or receive
+ *
+ * When modifying:
+ * - Messages are trimmed, stripped of control characters and limited to MAX_MESSAGE_LENGTH,
+ *   then run through the moderation word filter; muted identities are rejected
+ * - At most RATE_LIMIT_MESSAGES messages per RATE_LIMIT_WINDOW_SECONDS per identity
+ * - The table is public, so whispers are not private from clients that subscribe to everything
+ *
+ * Related files:
+ * - lib.rs: send_global_message / send_proximity_message / send_whisper reducers, calls init_chat
+ * - moderation.rs: Word filter, mutes and message reports
+ */
+
+use spacetimedb::{Identity, ReducerContext, ScheduleAt, SpacetimeType, Table, Timestamp};
+use std::time::Duration;
+
+use crate::moderation;
+use crate::{player, PlayerData};
+
+pub const MAX_MESSAGE_LENGTH: usize = 200;
+pub const PROXIMITY_CHAT_RADIUS: f32 = 20.0;
+const RATE_LIMIT_MESSAGES: u32 = 5;
+const RATE_LIMIT_WINDOW_SECONDS: u64 = 10;
+const MESSAGE_RETENTION_MICROS: i64 = 5 * 60 * 1_000_000;
+const PRUNE_INTERVAL_SECONDS: u64 = 30;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum ChatChannel {
+    Global,
+    Proximity,
+    Whisper,
+}
+
+#[spacetimedb::table(name = chat_message, public)]
+#[derive(Clone)]
+pub struct ChatMessage {
+    #[primary_key]
+    #[auto_inc]
+    pub message_id: u64,
+    pub channel: ChatChannel,
+    pub sender: Identity,
+    pub sender_name: String,
+    pub recipient: Option<Identity>,
+    pub text: String,
+    pub sent_at: Timestamp,
+}
+
+#[spacetimedb::table(name = chat_rate_limit)]
+#[derive(Clone)]
+pub struct ChatRateLimit {
+    #[primary_key]
+    pub identity: Identity,
+    pub window_start: Timestamp,
+    pub message_count: u32,
+}
+
+#[spacetimedb::table(name = chat_prune_schedule, scheduled(prune_chat_messages))]
+pub struct ChatPruneSchedule {
+    #[primary_key]
+    #[auto_inc]
+    scheduled_id: u64,
+    scheduled_at: ScheduleAt,
+}
+
+// Called from init: schedules message pruning
+pub fn init_chat(ctx: &ReducerContext) {
+    if ctx.db.chat_prune_schedule().count() == 0 {
+        spacetimedb::log::info!("[INIT] Scheduling chat pruning (every {} seconds)...", PRUNE_INTERVAL_SECONDS);
+        ctx.db.chat_prune_schedule().insert(ChatPruneSchedule {
+            scheduled_id: 0,
+            scheduled_at: ScheduleAt::Interval(Duration::from_secs(PRUNE_INTERVAL_SECONDS).into()),
+        });
+    }
+}
+
+pub fn send_global(ctx: &ReducerContext, text: String) -> Result<(), String> {
+    let (sender, text) = prepare_message(ctx, &text)?;
+    insert_message(ctx, ChatChannel::Global, &sender, None, text);
+    Ok(())
+}
+
+pub fn send_proximity(ctx: &ReducerContext, text: String) -> Result<(), String> {
+    let (sender, text) = prepare_message(ctx, &text)?;
+    let radius_sq = PROXIMITY_CHAT_RADIUS * PROXIMITY_CHAT_RADIUS;
+    let listeners: Vec<Identity> = ctx.db.player()
+        .iter()
+        .filter(|p| {
+            let dx = p.position.x - sender.position.x;
+            let dy = p.position.y - sender.position.y;
+            let dz = p.position.z - sender.position.z;
+            dx * dx + dy * dy + dz * dz <= radius_sq
+        })
+        .map(|p| p.identity)
+        .collect();
+    for listener in listeners {
+        insert_message(ctx, ChatChannel::Proximity, &sender, Some(listener), text.clone());
+    }
+    Ok(())
+}
+
+// Whispers to the player currently in the world under `target_name` (case-insensitive)
+pub fn send_whisper(ctx: &ReducerContext, target_name: String, text: String) -> Result<(), String> {
+    let lowered = target_name.trim().to_lowercase();
+    let target = ctx.db.player()
+        .iter()
+        .find(|p| p.username.to_lowercase() == lowered)
+        .ok_or_else(|| format!("'{}' is not online", target_name.trim()))?;
+    if target.identity == ctx.sender {
+        return Err("You can't whisper to yourself".to_string());
+    }
+    let (sender, text) = prepare_message(ctx, &text)?;
+    insert_message(ctx, ChatChannel::Whisper, &sender, Some(target.identity), text);
+    Ok(())
+}
+
+// Checks the sender, sanitizes and filters the text and applies the rate limit
+fn prepare_message(ctx: &ReducerContext, text: &str) -> Result<(PlayerData, String), String> {
+    let sender = ctx.db.player().identity().find(ctx.sender)
+        .ok_or_else(|| "You must be in the world to chat".to_string())?;
+    moderation::check_not_muted(ctx, ctx.sender)?;
+
+    let text: String = text.trim().chars().filter(|c| !c.is_control()).collect();
+    if text.is_empty() {
+        return Err("Message is empty".to_string());
+    }
+    if text.chars().count() > MAX_MESSAGE_LENGTH {
+        return Err(format!("Messages are limited to {} characters", MAX_MESSAGE_LENGTH));
+    }
+
+    let text = moderation::filter_message(ctx, &text)?;
+
+    check_rate_limit(ctx, ctx.sender)?;
+    Ok((sender, text))
+}
+
+fn check_rate_limit(ctx: &ReducerContext, identity: Identity) -> Result<(), String> {
+    let mut limit = match ctx.db.chat_rate_limit().identity().find(identity) {
+        Some(limit) => limit,
+        None => ctx.db.chat_rate_limit().insert(ChatRateLimit {
+            identity,
+            window_start: ctx.timestamp,
+            message_count: 0,
+        }),
+    };
+
+    let window_elapsed = ctx.timestamp
+        .duration_since(limit.window_start)
+        .map(|d| d.as_secs() >= RATE_LIMIT_WINDOW_SECONDS)
+        .unwrap_or(false);
+    if window_elapsed {
+        limit.window_start = ctx.timestamp;
+        limit.message_count = 0;
+    }
+    if limit.message_count >= RATE_LIMIT_MESSAGES {
+        return Err("You are sending messages too quickly".to_string());
+    }
+
+    limit.message_count += 1;
+    ctx.db.chat_rate_limit().identity().update(limit);
+    Ok(())
+}
+
+fn insert_message(ctx: &ReducerContext, channel: ChatChannel, sender: &PlayerData, recipient: Option<Identity>, text: String) {
+    ctx.db.chat_message().insert(ChatMessage {
+        message_id: 0,
+        channel,
+        sender: sender.identity,
+        sender_name: sender.username.clone(),
+        recipient,
+        text,
+        sent_at: ctx.timestamp,
+    });
+}
+
+// Deletes rate limit rows whose window has passed (a new row starts empty, so this changes
+// nothing); called from rate_limit::sweep_rate_limits. Returns the number of rows removed.
+pub fn sweep_rate_limits(ctx: &ReducerContext) -> usize {
+    let expired: Vec<ChatRateLimit> = ctx.db.chat_rate_limit()
+        .iter()
+        .filter(|limit| {
+            ctx.timestamp
+                .duration_since(limit.window_start)
+                .is_some_and(|d| d.as_secs() >= RATE_LIMIT_WINDOW_SECONDS)
+        })
+        .collect();
+    let removed = expired.len();
+    for limit in expired {
+        ctx.db.chat_rate_limit().delete(limit);
+    }
+    removed
+}
+
+#[spacetimedb::reducer]
+pub fn prune_chat_messages(ctx: &ReducerContext, _schedule: ChatPruneSchedule) -> Result<(), String> {
+    // Only the scheduler may prune messages
+    if ctx.sender != ctx.identity() {
+        return Err("prune_chat_messages may only be invoked by the scheduler".to_string());
+    }
+
+    let cutoff = Timestamp::from_micros_since_unix_epoch(
+        ctx.timestamp.to_micros_since_unix_epoch() - MESSAGE_RETENTION_MICROS,
+    );
+    let expired: Vec<ChatMessage> = ctx.db.chat_message()
+        .iter()
+        .filter(|m| m.sent_at < cutoff)
+        .collect();
+    for message in expired {
+        ctx.db.chat_message().delete(message);
+    }
+    Ok(())
+}
@@ -0,0 +1,143 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - classes.rs
+ *
+ * Data-driven character class definitions.
+ *
+ * Key components:
+ * - ClassDefinition: Public table of base stats, speed, melee profile, spells and animations
+ * - init_classes: Seeds the default Wizard and Paladin classes
+ * - find_class: Looks up a class by name (used to validate create_character)
+ * - class_stats: Class lookup with a fallback built from common.rs defaults
+ *
+ * When modifying:
+ * - Class names must match the options in client/src/components/JoinGameDialog.tsx
+ * - allowed_animations lists the client clips a class may play ("attack1", "cast", ...)
+ *
+ * Related files:
+ * - characters.rs: create_character validates against this table
+ * - player_logic.rs, validation.rs: Read speed_multiplier
+ * - combat.rs, spells.rs: Read melee stats and spell abilities
+ */
+
+use spacetimedb::{ReducerContext, Table};
+
+pub const DEFAULT_BASE_HEALTH: i32 = 100;
+pub const DEFAULT_BASE_MANA: i32 = 100;
+
+#[spacetimedb::table(name = class_definition, public)]
+#[derive(Clone)]
+pub struct ClassDefinition {
+    #[primary_key]
+    pub class_name: String,
+    pub base_health: i32,
+    pub base_mana: i32,
+    pub speed_multiplier: f32,
+    pub melee_damage: i32,
+    pub melee_cooldown_seconds: f32,
+    pub melee_range: f32,
+    // Half-angle of the melee hit cone in radians
+    pub melee_cone_half_angle: f32,
+    pub default_spell_id: u32,
+    pub spell_ids: Vec<u32>,
+    pub allowed_animations: Vec<String>,
+}
+
+fn animations(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+// Called from init: seeds the default classes
+pub fn init_classes(ctx: &ReducerContext) {
+    let defaults = [
+        ClassDefinition {
+            class_name: "Wizard".to_string(),
+            base_health: 80,
+            base_mana: 150,
+            speed_multiplier: 1.0,
+            melee_damage: 6,
+            melee_cooldown_seconds: 1.2,
+            melee_range: 1.8,
+            melee_cone_half_angle: 0.7,
+            default_spell_id: crate::spells::SPELL_FIREBALL,
+            spell_ids: vec![crate::spells::SPELL_FIREBALL, crate::spells::SPELL_HEAL],
+            allowed_animations: animations(&["idle", "walk", "run", "jump", "attack1", "cast", "damage", "death"]),
+        },
+        ClassDefinition {
+            class_name: "Paladin".to_string(),
+            base_health: 130,
+            base_mana: 60,
+            speed_multiplier: 0.9,
+            melee_damage: 15,
+            melee_cooldown_seconds: 0.8,
+            melee_range: 2.5,
+            melee_cone_half_angle: 1.0,
+            default_spell_id: crate::spells::SPELL_HEAL,
+            spell_ids: vec![crate::spells::SPELL_HEAL],
+            allowed_animations: animations(&["idle", "walk", "run", "jump", "attack1", "cast", "damage", "death"]),
+        },
+    ];
+    for class in defaults {
+        if ctx.db.class_definition().class_name().find(&class.class_name).is_none() {
+            ctx.db.class_definition().insert(class);
+        }
+    }
+}
+
+pub fn find_class(ctx: &ReducerContext, class_name: &str) -> Option<ClassDefinition> {
+    ctx.db.class_definition().class_name().find(&class_name.to_string())
+}
+
+// Class stats for an existing player; rows created before a class was removed fall back to defaults
+pub fn class_stats(ctx: &ReducerContext, class_name: &str) -> ClassDefinition {
+    find_class(ctx, class_name).unwrap_or_else(|| ClassDefinition {
+        class_name: class_name.to_string(),
+        base_health: DEFAULT_BASE_HEALTH,
+        base_mana: DEFAULT_BASE_MANA,
+        speed_multiplier: 1.0,
+        melee_damage: 10,
+        melee_cooldown_seconds: 1.0,
+        melee_range: 2.0,
+        melee_cone_half_angle: 0.8,
+        default_spell_id: crate::spells::SPELL_FIREBALL,
+        spell_ids: Vec::new(),
+        allowed_animations: Vec::new(),
+    })
+}
+
+// Whether the class may play an animation clip; an empty list allows everything
+pub fn allows_animation(class: &ClassDefinition, animation: &str) -> bool {
+    class.allowed_animations.is_empty() || class.allowed_animations.iter().any(|a| a == animation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class_with(allowed_animations: Vec<String>) -> ClassDefinition {
+        ClassDefinition {
+            class_name: "Tester".to_string(),
+            base_health: DEFAULT_BASE_HEALTH,
+            base_mana: DEFAULT_BASE_MANA,
+            speed_multiplier: 1.0,
+            melee_damage: 10,
+            melee_cooldown_seconds: 1.0,
+            melee_range: 2.0,
+            melee_cone_half_angle: 0.8,
+            default_spell_id: 0,
+            spell_ids: Vec::new(),
+            allowed_animations,
+        }
+    }
+
+    #[test]
+    fn empty_animation_list_allows_everything() {
+        assert!(allows_animation(&class_with(Vec::new()), "cast"));
+    }
+
+    #[test]
+    fn animation_list_restricts_clips() {
+        let class = class_with(animations(&["idle", "attack1"]));
+        assert!(allows_animation(&class, "attack1"));
+        assert!(!allows_animation(&class, "cast"));
+    }
+}
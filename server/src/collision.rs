@@ -0,0 +1,475 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - collision.rs
+ *
+ * Static collision geometry and collision resolution for player movement.
+ *
+ * Key components:
+ * - Obstacle: Public table of static shapes (axis-aligned boxes, upright cylinders, spheres)
+ * - ObstacleCell: Broadphase grid; one row per grid cell an obstacle's bounds overlap
+ * - CollisionWorld: Per-reducer query helper that loads grid cells on demand and caches them
+ *   - slide: Collide-and-slide from one position to another (sub-stepped, so fast moves
+ *     can't tunnel through thin walls), kept inside the horizontal world bounds
+ *   - is_blocked: Whether a player standing at a position would overlap an obstacle
+ * - separate_players: Pushes overlapping player capsules apart (called from game_tick)
+ * - add_obstacle / remove_obstacle: Admin-only geometry editing, kept in sync with the grid
+ *
+ * Collision model:
+ * - Players are upright capsules of PLAYER_RADIUS / PLAYER_HEIGHT standing on their position;
+ *   against obstacles they are treated as the capsule's cylinder
+ * - Resolution is horizontal only: height always comes from the terrain
+ * - Obstacles entirely above the player's head or below their feet are ignored
+ *
+ * When modifying:
+ * - Always insert and delete obstacles through insert_obstacle / remove_obstacle so the
+ *   broadphase grid stays consistent
+ * - GRID_CELL_SIZE should stay larger than the player diameter
+ *
+ * Related files:
+ * - player_logic.rs: calculate_new_position slides along obstacles; update_players_logic
+ *   separates players
+ * - lib.rs: Obstacle admin reducers, calls init_collision
+ * - common.rs: PLAYER_RADIUS and PLAYER_HEIGHT
+ * - bounds.rs: World bounds applied by slide
+ * - spawn.rs: Saved positions that are now blocked aren't restored
+ */
+
+use spacetimedb::{ReducerContext, SpacetimeType, Table};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use crate::admin::{self, Role};
+use crate::bounds::{self, WorldBounds};
+use crate::common::{Vector3, PLAYER_HEIGHT, PLAYER_RADIUS};
+use crate::terrain::Terrain;
+use crate::PlayerData;
+
+const GRID_CELL_SIZE: f32 = 8.0;
+// Push-out passes per step; each pass resolves the deepest remaining penetration
+const MAX_RESOLVE_ITERATIONS: usize = 4;
+// Upper bound on sub-steps for one slide (moves longer than this many radii may tunnel)
+const MAX_SLIDE_STEPS: usize = 32;
+// Extra distance added when pushing out, so the next query doesn't touch the same surface
+const SKIN: f32 = 0.001;
+// Keeps admin-created obstacles from registering in an unbounded number of grid cells
+const MAX_OBSTACLE_EXTENT: f32 = 64.0;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum ObstacleShape {
+    Aabb,
+    Cylinder,
+    Sphere,
+}
+
+#[spacetimedb::table(name = obstacle, public)]
+#[derive(Clone)]
+pub struct Obstacle {
+    #[primary_key]
+    #[auto_inc]
+    pub obstacle_id: u64,
+    pub shape: ObstacleShape,
+    pub center: Vector3,
+    // Aabb only
+    pub half_extents: Vector3,
+    // Cylinder and Sphere
+    pub radius: f32,
+    // Cylinder only: full height, centred on `center`
+    pub height: f32,
+}
+
+#[spacetimedb::table(name = obstacle_cell)]
+#[derive(Clone)]
+pub struct ObstacleCell {
+    #[primary_key]
+    #[auto_inc]
+    pub entry_id: u64,
+    #[index(btree)]
+    pub cell: u64,
+    #[index(btree)]
+    pub obstacle_id: u64,
+}
+
+impl Obstacle {
+    // Half size of the horizontal bounding rectangle (x, z)
+    fn horizontal_half_extents(&self) -> (f32, f32) {
+        match self.shape {
+            ObstacleShape::Aabb => (self.half_extents.x, self.half_extents.z),
+            ObstacleShape::Cylinder | ObstacleShape::Sphere => (self.radius, self.radius),
+        }
+    }
+
+    fn vertical_range(&self) -> (f32, f32) {
+        let half_height = match self.shape {
+            ObstacleShape::Aabb => self.half_extents.y,
+            ObstacleShape::Cylinder => self.height / 2.0,
+            ObstacleShape::Sphere => self.radius,
+        };
+        (self.center.y - half_height, self.center.y + half_height)
+    }
+
+    // Horizontal push-out for a cylinder of `radius` at (x, z) spanning [bottom, top]:
+    // returns the unit direction (x, z) and the penetration depth, or None if not touching
+    fn penetration(&self, x: f32, z: f32, bottom: f32, top: f32, radius: f32) -> Option<(f32, f32, f32)> {
+        let (min_y, max_y) = self.vertical_range();
+        if top <= min_y || bottom >= max_y {
+            return None;
+        }
+        let dx = x - self.center.x;
+        let dz = z - self.center.z;
+        match self.shape {
+            ObstacleShape::Aabb => {
+                let (hx, hz) = self.horizontal_half_extents();
+                let ox = x - dx.clamp(-hx, hx) - self.center.x;
+                let oz = z - dz.clamp(-hz, hz) - self.center.z;
+                let distance = (ox * ox + oz * oz).sqrt();
+                if distance > 0.0001 {
+                    return (distance < radius).then(|| (ox / distance, oz / distance, radius - distance));
+                }
+                // Centre is inside the box: leave through the nearest face
+                let faces = [
+                    (-1.0, 0.0, hx + dx),
+                    (1.0, 0.0, hx - dx),
+                    (0.0, -1.0, hz + dz),
+                    (0.0, 1.0, hz - dz),
+                ];
+                faces
+                    .into_iter()
+                    .min_by(|a, b| a.2.total_cmp(&b.2))
+                    .map(|(nx, nz, depth)| (nx, nz, depth + radius))
+            }
+            ObstacleShape::Cylinder => circle_penetration(dx, dz, self.radius + radius),
+            ObstacleShape::Sphere => {
+                // Widest horizontal slice of the sphere within the player's vertical span
+                // (max/min rather than clamp, which panics on NaN bounds)
+                let dy = self.center.y - self.center.y.max(bottom).min(top);
+                let slice = (self.radius * self.radius - dy * dy).max(0.0).sqrt();
+                circle_penetration(dx, dz, slice + radius)
+            }
+        }
+    }
+}
+
+fn circle_penetration(dx: f32, dz: f32, combined_radius: f32) -> Option<(f32, f32, f32)> {
+    let distance = (dx * dx + dz * dz).sqrt();
+    if distance >= combined_radius {
+        return None;
+    }
+    if distance < 0.0001 {
+        return Some((1.0, 0.0, combined_radius));
+    }
+    Some((dx / distance, dz / distance, combined_radius - distance))
+}
+
+fn cell_coord(value: f32) -> i32 {
+    (value / GRID_CELL_SIZE).floor() as i32
+}
+
+// Packs grid coordinates into the ObstacleCell.cell key
+fn cell_key(cell_x: i32, cell_z: i32) -> u64 {
+    ((cell_x as u32 as u64) << 32) | cell_z as u32 as u64
+}
+
+// Called from init (after init_terrain): places a few demo obstacles outside the spawn ring
+pub fn init_collision(ctx: &ReducerContext) {
+    if ctx.db.obstacle().count() > 0 {
+        return;
+    }
+    spacetimedb::log::info!("[INIT] Placing default obstacles...");
+    let terrain = Terrain::new(ctx);
+    let on_ground = |x: f32, z: f32, half_height: f32| Vector3 { x, y: terrain.height_at(x, z) + half_height, z };
+    let none = Vector3 { x: 0.0, y: 0.0, z: 0.0 };
+
+    // A wall, a pair of pillars and a boulder
+    insert_obstacle(ctx, Obstacle {
+        obstacle_id: 0,
+        shape: ObstacleShape::Aabb,
+        center: on_ground(0.0, -22.0, 1.5),
+        half_extents: Vector3 { x: 8.0, y: 1.5, z: 0.5 },
+        radius: 0.0,
+        height: 0.0,
+    });
+    for x in [-6.0, 6.0] {
+        insert_obstacle(ctx, Obstacle {
+            obstacle_id: 0,
+            shape: ObstacleShape::Cylinder,
+            center: on_ground(x, 22.0, 3.0),
+            half_extents: none.clone(),
+            radius: 1.0,
+            height: 6.0,
+        });
+    }
+    insert_obstacle(ctx, Obstacle {
+        obstacle_id: 0,
+        shape: ObstacleShape::Sphere,
+        center: on_ground(22.0, 0.0, 0.5),
+        half_extents: none,
+        radius: 2.0,
+        height: 0.0,
+    });
+}
+
+// Inserts an obstacle and registers it in every grid cell its bounds overlap
+fn insert_obstacle(ctx: &ReducerContext, obstacle: Obstacle) -> Obstacle {
+    let obstacle = ctx.db.obstacle().insert(obstacle);
+    let (hx, hz) = obstacle.horizontal_half_extents();
+    for cell_x in cell_coord(obstacle.center.x - hx)..=cell_coord(obstacle.center.x + hx) {
+        for cell_z in cell_coord(obstacle.center.z - hz)..=cell_coord(obstacle.center.z + hz) {
+            ctx.db.obstacle_cell().insert(ObstacleCell {
+                entry_id: 0,
+                cell: cell_key(cell_x, cell_z),
+                obstacle_id: obstacle.obstacle_id,
+            });
+        }
+    }
+    obstacle
+}
+
+pub fn add_obstacle(
+    ctx: &ReducerContext,
+    shape: ObstacleShape,
+    center: Vector3,
+    half_extents: Vector3,
+    radius: f32,
+    height: f32,
+) -> Result<(), String> {
+    admin::require_role(ctx, Role::Admin)?;
+    bounds::require_finite(&center, "center")?;
+    bounds::require_finite(&half_extents, "half extents")?;
+    let valid_size = |v: f32| v.is_finite() && v > 0.0 && v <= MAX_OBSTACLE_EXTENT;
+    let valid = match shape {
+        ObstacleShape::Aabb => valid_size(half_extents.x) && valid_size(half_extents.y) && valid_size(half_extents.z),
+        ObstacleShape::Cylinder => valid_size(radius) && valid_size(height),
+        ObstacleShape::Sphere => valid_size(radius),
+    };
+    if !valid {
+        return Err(format!("Obstacle dimensions must be positive and at most {}", MAX_OBSTACLE_EXTENT));
+    }
+
+    let obstacle = insert_obstacle(ctx, Obstacle { obstacle_id: 0, shape, center, half_extents, radius, height });
+    admin::audit(ctx, "add_obstacle", None, format!("{} {:?} at {:?}", obstacle.obstacle_id, shape, obstacle.center));
+    Ok(())
+}
+
+pub fn remove_obstacle(ctx: &ReducerContext, obstacle_id: u64) -> Result<(), String> {
+    admin::require_role(ctx, Role::Admin)?;
+    if !ctx.db.obstacle().obstacle_id().delete(obstacle_id) {
+        return Err("Obstacle not found".to_string());
+    }
+    let cells: Vec<ObstacleCell> = ctx.db.obstacle_cell().obstacle_id().filter(&obstacle_id).collect();
+    for cell in cells {
+        ctx.db.obstacle_cell().delete(cell);
+    }
+    admin::audit(ctx, "remove_obstacle", None, obstacle_id.to_string());
+    Ok(())
+}
+
+// Obstacle queries for one reducer call; each grid cell is read from the tables at most once
+pub struct CollisionWorld<'a> {
+    ctx: &'a ReducerContext,
+    bounds: WorldBounds,
+    cells: RefCell<HashMap<u64, Vec<Obstacle>>>,
+}
+
+impl<'a> CollisionWorld<'a> {
+    pub fn new(ctx: &'a ReducerContext) -> Self {
+        CollisionWorld { ctx, bounds: bounds::world_bounds(ctx), cells: RefCell::new(HashMap::new()) }
+    }
+
+    // Obstacles registered in any grid cell overlapping the given horizontal bounds
+    fn nearby(&self, min_x: f32, min_z: f32, max_x: f32, max_z: f32) -> Vec<Obstacle> {
+        let mut cache = self.cells.borrow_mut();
+        let mut seen = HashSet::new();
+        let mut found = Vec::new();
+        for cell_x in cell_coord(min_x)..=cell_coord(max_x) {
+            for cell_z in cell_coord(min_z)..=cell_coord(max_z) {
+                let key = cell_key(cell_x, cell_z);
+                let obstacles = cache.entry(key).or_insert_with(|| {
+                    self.ctx.db.obstacle_cell()
+                        .cell()
+                        .filter(&key)
+                        .filter_map(|entry| self.ctx.db.obstacle().obstacle_id().find(entry.obstacle_id))
+                        .collect()
+                });
+                for obstacle in obstacles.iter() {
+                    if seen.insert(obstacle.obstacle_id) {
+                        found.push(obstacle.clone());
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    // Pushes a player standing at (x, feet_y, z) out of every obstacle it overlaps
+    fn resolve(&self, mut x: f32, mut z: f32, feet_y: f32) -> (f32, f32) {
+        let reach = PLAYER_RADIUS * 2.0;
+        let candidates = self.nearby(x - reach, z - reach, x + reach, z + reach);
+        if candidates.is_empty() {
+            return (x, z);
+        }
+        for _ in 0..MAX_RESOLVE_ITERATIONS {
+            let deepest = candidates
+                .iter()
+                .filter_map(|o| o.penetration(x, z, feet_y, feet_y + PLAYER_HEIGHT, PLAYER_RADIUS))
+                .max_by(|a, b| a.2.total_cmp(&b.2));
+            let Some((nx, nz, depth)) = deepest else {
+                break;
+            };
+            x += nx * (depth + SKIN);
+            z += nz * (depth + SKIN);
+        }
+        (x, z)
+    }
+
+    // True if a player standing at `position` would overlap any obstacle
+    pub fn is_blocked(&self, position: &Vector3) -> bool {
+        let reach = PLAYER_RADIUS * 2.0;
+        self.nearby(position.x - reach, position.z - reach, position.x + reach, position.z + reach)
+            .iter()
+            .any(|o| o.penetration(position.x, position.z, position.y, position.y + PLAYER_HEIGHT, PLAYER_RADIUS).is_some())
+    }
+
+    // Moves a player from `from` towards `to`, sliding along any obstacles in the way and
+    // along the edge of the world. Only X/Z are resolved; the returned Y is `to.y`.
+    pub fn slide(&self, from: &Vector3, to: &Vector3) -> Vector3 {
+        let dx = to.x - from.x;
+        let dz = to.z - from.z;
+        let distance = (dx * dx + dz * dz).sqrt();
+        let steps = ((distance / PLAYER_RADIUS).ceil() as usize).clamp(1, MAX_SLIDE_STEPS);
+
+        let (mut x, mut z) = (from.x, from.z);
+        for _ in 0..steps {
+            (x, z) = self.resolve(x + dx / steps as f32, z + dz / steps as f32, from.y);
+        }
+        self.bounds.clamp_horizontal(&Vector3 { x, y: to.y, z }, PLAYER_RADIUS)
+    }
+}
+
+// Pushes apart living players whose capsules overlap, half the overlap each, then keeps
+// them out of obstacles and on (or above) the ground. Returns the indices of players that moved.
+pub fn separate_players(players: &mut [PlayerData], world: &CollisionWorld, terrain: &Terrain) -> Vec<usize> {
+    // Broadphase: bucket players by grid cell and only test neighbouring cells
+    let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+    for (index, player) in players.iter().enumerate() {
+        if !player.is_dead {
+            grid.entry((cell_coord(player.position.x), cell_coord(player.position.z))).or_default().push(index);
+        }
+    }
+
+    let min_distance = PLAYER_RADIUS * 2.0;
+    let mut offsets: HashMap<usize, (f32, f32)> = HashMap::new();
+    for (&(cell_x, cell_z), members) in grid.iter() {
+        for &a in members {
+            for neighbour_x in cell_x - 1..=cell_x + 1 {
+                for neighbour_z in cell_z - 1..=cell_z + 1 {
+                    let Some(others) = grid.get(&(neighbour_x, neighbour_z)) else {
+                        continue;
+                    };
+                    // Each pair is handled once, from its lower index
+                    for &b in others.iter().filter(|&&b| b > a) {
+                        let (pa, pb) = (&players[a].position, &players[b].position);
+                        if (pa.y - pb.y).abs() >= PLAYER_HEIGHT {
+                            continue;
+                        }
+                        let Some((nx, nz, depth)) = circle_penetration(pa.x - pb.x, pa.z - pb.z, min_distance) else {
+                            continue;
+                        };
+                        let push = depth / 2.0 + SKIN;
+                        let offset_a = offsets.entry(a).or_insert((0.0, 0.0));
+                        offset_a.0 += nx * push;
+                        offset_a.1 += nz * push;
+                        let offset_b = offsets.entry(b).or_insert((0.0, 0.0));
+                        offset_b.0 -= nx * push;
+                        offset_b.1 -= nz * push;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut moved = Vec::with_capacity(offsets.len());
+    for (index, (ox, oz)) in offsets {
+        let player = &mut players[index];
+        let pushed = Vector3 { x: player.position.x + ox, y: player.position.y, z: player.position.z + oz };
+        let resolved = world.slide(&player.position, &pushed);
+        // Never shove a player up a cliff they couldn't walk up
+        if !terrain.is_walkable(&player.position, &resolved) {
+            continue;
+        }
+        player.position = resolved;
+        crate::player_logic::settle_on_terrain(terrain, player);
+        moved.push(index);
+    }
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    fn obstacle(shape: ObstacleShape, center_y: f32, half_extents: f32, radius: f32, height: f32) -> Obstacle {
+        Obstacle {
+            obstacle_id: 0,
+            shape,
+            center: Vector3 { x: 0.0, y: center_y, z: 0.0 },
+            half_extents: Vector3 { x: half_extents, y: half_extents, z: half_extents },
+            radius,
+            height,
+        }
+    }
+
+    #[test]
+    fn box_pushes_out_of_the_nearest_side() {
+        let crate_box = obstacle(ObstacleShape::Aabb, 1.0, 1.0, 0.0, 0.0);
+        let (nx, nz, depth) = crate_box.penetration(1.3, 0.0, 0.0, 2.0, 0.5).unwrap();
+        assert!(approx(nx, 1.0) && approx(nz, 0.0) && approx(depth, 0.2));
+        assert!(crate_box.penetration(1.6, 0.0, 0.0, 2.0, 0.5).is_none());
+    }
+
+    #[test]
+    fn box_pushes_an_enclosed_centre_through_the_nearest_face() {
+        let crate_box = obstacle(ObstacleShape::Aabb, 1.0, 1.0, 0.0, 0.0);
+        let (nx, nz, depth) = crate_box.penetration(0.8, 0.0, 0.0, 2.0, 0.5).unwrap();
+        assert!(approx(nx, 1.0) && approx(nz, 0.0) && approx(depth, 0.7));
+    }
+
+    #[test]
+    fn obstacles_outside_the_vertical_span_are_ignored() {
+        let crate_box = obstacle(ObstacleShape::Aabb, 1.0, 1.0, 0.0, 0.0);
+        assert!(crate_box.penetration(0.0, 0.0, 2.0, 3.8, 0.5).is_none());
+        let pillar = obstacle(ObstacleShape::Cylinder, 1.0, 0.0, 1.0, 2.0);
+        assert!(pillar.penetration(0.0, 0.0, -3.0, -0.5, 0.5).is_none());
+    }
+
+    #[test]
+    fn cylinder_uses_the_combined_radius() {
+        let pillar = obstacle(ObstacleShape::Cylinder, 1.0, 0.0, 1.0, 2.0);
+        let (nx, nz, depth) = pillar.penetration(0.0, 1.2, 0.0, 1.8, 0.5).unwrap();
+        assert!(approx(nx, 0.0) && approx(nz, 1.0) && approx(depth, 0.3));
+    }
+
+    #[test]
+    fn sphere_uses_the_widest_slice_the_player_overlaps() {
+        let boulder = obstacle(ObstacleShape::Sphere, 3.0, 0.0, 1.0, 0.0);
+        let slice = (1.0f32 - 0.5 * 0.5).sqrt();
+        let (_, _, depth) = boulder.penetration(1.2, 0.0, 0.0, 2.5, 0.5).unwrap();
+        assert!(approx(depth, slice + 0.5 - 1.2));
+    }
+
+    #[test]
+    fn coincident_circles_still_get_a_direction() {
+        assert_eq!(circle_penetration(0.0, 0.0, 1.0), Some((1.0, 0.0, 1.0)));
+        assert!(circle_penetration(1.0, 0.0, 1.0).is_none());
+    }
+
+    #[test]
+    fn grid_cells_are_distinct_across_the_origin() {
+        assert_eq!(cell_coord(-0.1), -1);
+        assert_eq!(cell_coord(GRID_CELL_SIZE), 1);
+        assert_ne!(cell_key(-1, 0), cell_key(0, -1));
+        assert_ne!(cell_key(-1, -1), cell_key(0, 0));
+    }
+}
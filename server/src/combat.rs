@@ -0,0 +1,186 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - combat.rs
+ *
+ * Melee combat resolution for InputState.attack.
+ *
+ * Key components:
+ * - CombatEvent: Public log of hits (attacker, target, damage) for client hit effects
+ * - try_melee_attack: Validates the cooldown, plays the attack and damages targets in the cone
+ * - in_attack_cooldown: Whether the melee cooldown is still running (also blocks character switches)
+ * - apply_damage: Shared damage path for melee and spells (hurt, events, death)
+ * - prune_combat_events: Removes old events (called from game_tick)
+ *
+ * When modifying:
+ * - Attacks only start when the cooldown has elapsed, so the Attack animation can't be spoofed
+ * - Cooldown, range, hit cone and damage come from the attacker's ClassDefinition
+ * - Facing comes from rotation.y, using the same forward direction as movement
+ * - Damage interrupts the target's spell cast
+ *
+ * Related files:
+ * - lib.rs: Calls try_melee_attack from update_player_input
+ * - animation.rs: Attack and Hurt states
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+
+use crate::animation;
+use crate::classes::{self, ClassDefinition};
+use crate::common::AnimationState;
+use crate::player_logic;
+use crate::spawn;
+use crate::spells;
+use crate::{player, PlayerData};
+
+#[spacetimedb::table(name = combat_event, public)]
+#[derive(Clone)]
+pub struct CombatEvent {
+    #[primary_key]
+    #[auto_inc]
+    pub event_id: u64,
+    pub attacker: Identity,
+    pub target: Identity,
+    pub damage: i32,
+    pub timestamp: Timestamp,
+}
+
+// How long combat events are kept before pruning
+const COMBAT_EVENT_RETENTION_MICROS: i64 = 10_000_000;
+
+// True while the player's class melee cooldown since their last attack hasn't elapsed
+pub fn in_attack_cooldown(ctx: &ReducerContext, player: &PlayerData) -> bool {
+    let since_last_attack = ctx.timestamp
+        .duration_since(player.last_attack_at)
+        .map(|d| d.as_secs_f32())
+        .unwrap_or(0.0);
+    since_last_attack < classes::class_stats(ctx, &player.character_class).melee_cooldown_seconds
+}
+
+// Starts a melee attack if the attacker's cooldown has elapsed and applies damage to
+// every other player inside the hit cone. Returns true if an attack happened.
+pub fn try_melee_attack(ctx: &ReducerContext, attacker: &mut PlayerData) -> bool {
+    if attacker.is_dead {
+        return false;
+    }
+
+    let profile = classes::class_stats(ctx, &attacker.character_class);
+    if !classes::allows_animation(&profile, "attack1") {
+        return false;
+    }
+    if in_attack_cooldown(ctx, attacker) {
+        return false;
+    }
+    if !animation::play_action(attacker, AnimationState::Attack, ctx.timestamp) {
+        return false;
+    }
+    attacker.last_attack_at = ctx.timestamp;
+
+    let facing = player_logic::facing_direction(&attacker.rotation);
+    let targets: Vec<PlayerData> = ctx.db.player()
+        .iter()
+        .filter(|p| p.identity != attacker.identity && !p.is_dead)
+        .filter(|p| in_hit_cone(attacker, p, facing.x, facing.z, &profile))
+        .collect();
+
+    for mut target in targets {
+        apply_damage(ctx, attacker.identity, &mut target, profile.melee_damage);
+        ctx.db.player().identity().update(target);
+    }
+    true
+}
+
+// Applies damage from any source: interrupts casts, plays Hurt, logs a CombatEvent and
+// kills the target when health reaches zero. The caller writes the target row back.
+pub fn apply_damage(ctx: &ReducerContext, attacker: Identity, target: &mut PlayerData, damage: i32) {
+    if target.is_dead {
+        return;
+    }
+    target.health = (target.health - damage).max(0);
+    spells::interrupt_cast(ctx, target, "damage");
+    animation::play_action(target, AnimationState::Hurt, ctx.timestamp);
+    ctx.db.combat_event().insert(CombatEvent {
+        event_id: 0,
+        attacker,
+        target: target.identity,
+        damage,
+        timestamp: ctx.timestamp,
+    });
+    if target.health == 0 {
+        spawn::kill_player(ctx, target);
+    }
+}
+
+fn in_hit_cone(attacker: &PlayerData, target: &PlayerData, facing_x: f32, facing_z: f32, profile: &ClassDefinition) -> bool {
+    let dx = target.position.x - attacker.position.x;
+    let dz = target.position.z - attacker.position.z;
+    let distance = (dx * dx + dz * dz).sqrt();
+    if distance > profile.melee_range {
+        return false;
+    }
+    if distance < 0.01 {
+        return true;
+    }
+    let cos_angle = (dx * facing_x + dz * facing_z) / distance;
+    cos_angle >= profile.melee_cone_half_angle.cos()
+}
+
+pub fn prune_combat_events(ctx: &ReducerContext) {
+    let cutoff = Timestamp::from_micros_since_unix_epoch(
+        ctx.timestamp.to_micros_since_unix_epoch() - COMBAT_EVENT_RETENTION_MICROS,
+    );
+    let expired: Vec<CombatEvent> = ctx.db.combat_event()
+        .iter()
+        .filter(|e| e.timestamp < cutoff)
+        .collect();
+    for event in expired {
+        ctx.db.combat_event().delete(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Vector3;
+
+    fn profile() -> ClassDefinition {
+        ClassDefinition {
+            class_name: "Tester".to_string(),
+            base_health: 100,
+            base_mana: 100,
+            speed_multiplier: 1.0,
+            melee_damage: 10,
+            melee_cooldown_seconds: 1.0,
+            melee_range: 2.0,
+            melee_cone_half_angle: 0.8,
+            default_spell_id: 0,
+            spell_ids: Vec::new(),
+            allowed_animations: Vec::new(),
+        }
+    }
+
+    fn target_at(x: f32, z: f32) -> PlayerData {
+        let mut target = crate::test_player();
+        target.position = Vector3 { x, y: 0.0, z };
+        target
+    }
+
+    #[test]
+    fn targets_in_front_and_in_range_are_hit() {
+        let attacker = crate::test_player();
+        assert!(in_hit_cone(&attacker, &target_at(0.0, 1.5), 0.0, 1.0, &profile()));
+        assert!(in_hit_cone(&attacker, &target_at(0.5, 1.5), 0.0, 1.0, &profile()));
+    }
+
+    #[test]
+    fn targets_out_of_range_or_behind_are_missed() {
+        let attacker = crate::test_player();
+        assert!(!in_hit_cone(&attacker, &target_at(0.0, 2.5), 0.0, 1.0, &profile()));
+        assert!(!in_hit_cone(&attacker, &target_at(0.0, -1.0), 0.0, 1.0, &profile()));
+        assert!(!in_hit_cone(&attacker, &target_at(1.5, 0.2), 0.0, 1.0, &profile()));
+    }
+
+    #[test]
+    fn overlapping_targets_are_always_hit() {
+        let attacker = crate::test_player();
+        assert!(in_hit_cone(&attacker, &target_at(0.0, 0.0), 0.0, 1.0, &profile()));
+    }
+}
@@ -6,7 +6,14 @@
  * Key components:
  * - Vector3: 3D vector struct for positions, rotations and movement
  * - InputState: Player input tracking with all possible input actions
+ * - AnimationState: Server-driven animation state stored on each player
  * - Game constants: Speed values that affect player movement
+ * - Movement authority constants: Client position tolerance and input integration limits
+ * - Simulation constants: Fixed tick rate and catch-up limits for game_tick
+ * - Validation constants: Movement tolerance, auto-kick thresholds and kick cooldown
+ * - Collision constants: Player capsule dimensions
+ * - Vertical physics constants: Gravity, jump impulse, coyote time and ground snapping
+ * - Mana constants: Regeneration rate applied from game_tick
  * 
  * These structures are used by:
  * - lib.rs: For database table definitions
@@ -16,6 +23,7 @@
  * - Changes to Vector3 or InputState will affect database schema
  * - You may need to run 'spacetime delete <db_name>' after schema changes
  * - Adjust PLAYER_SPEED and SPRINT_MULTIPLIER to change movement feel
+ * - SERVER_AUTHORITATIVE_MOVEMENT toggles whether the server integrates input or trusts client positions
  * - Adding new input types requires updates to InputState and UI event handlers
  */
 
@@ -31,6 +39,13 @@ pub struct Vector3 {
     pub z: f32,
 }
 
+impl Vector3 {
+    // False if any component is NaN or infinite
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+}
+
 // Helper struct for player input state
 #[derive(SpacetimeType, Clone, Debug)]
 pub struct InputState {
@@ -45,7 +60,80 @@ pub struct InputState {
     pub sequence: u32,
 }
 
+// Server-side animation state (see animation.rs for transitions)
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum AnimationState {
+    Idle,
+    Walk,
+    Run,
+    Jump,
+    Attack,
+    Cast,
+    Hurt,
+    Dead,
+}
+
 // --- Game Constants ---
 
 pub const PLAYER_SPEED: f32 = 7.5;
 pub const SPRINT_MULTIPLIER: f32 = 1.8;
+
+// --- Movement Authority ---
+
+// When true, update_player_input integrates InputState on the server and ignores the
+// client-reported position. When false, the client is trusted.
+pub const SERVER_AUTHORITATIVE_MOVEMENT: bool = true;
+// Horizontal distance (world units) within which a client's predicted position is accepted
+// as a hint; further away it is ignored and the divergence logged
+pub const CLIENT_POSITION_TOLERANCE: f32 = 1.0;
+// Upper bound on the elapsed time integrated for a single input, so stalls don't cause big jumps
+pub const MAX_INPUT_DELTA_SECONDS: f32 = 0.25;
+
+// --- Simulation ---
+
+// Fixed simulation rate for game_tick (20-60 Hz is a sensible range). Client prediction in
+// client/src/components/Player.tsx steps at the same rate; change both together.
+pub const SIMULATION_TICK_RATE_HZ: u32 = 30;
+// Max fixed steps a player is advanced by in a single tick when catching up after a stall;
+// older time is dropped
+pub const MAX_CATCH_UP_STEPS: u32 = 4;
+
+// --- Movement Validation ---
+
+// Extra distance (world units) tolerated on top of max sprint speed, to absorb jitter. A move
+// can drop one accepted hint and add another, so this covers CLIENT_POSITION_TOLERANCE twice.
+pub const MOVEMENT_VALIDATION_SLACK: f32 = 2.0 * CLIENT_POSITION_TOLERANCE;
+// Defaults for the admin-settable MovementValidationConfig (see validation.rs):
+// Moves longer than this multiple of the allowed distance are rejected instead of clamped
+pub const DEFAULT_TELEPORT_DISTANCE_FACTOR: f32 = 3.0;
+// Sliding window over which violations are counted
+pub const DEFAULT_VIOLATION_WINDOW_SECONDS: u32 = 30;
+// Violations within the window that trigger an automatic kick
+pub const DEFAULT_VIOLATION_KICK_THRESHOLD: u32 = 10;
+// How long a player kicked for violations is kept out of the world
+pub const DEFAULT_KICK_COOLDOWN_SECONDS: u32 = 60;
+
+// --- Collision ---
+
+// Players collide as an upright capsule standing on their position
+pub const PLAYER_RADIUS: f32 = 0.4;
+pub const PLAYER_HEIGHT: f32 = 1.8;
+
+// --- Vertical Physics ---
+
+// Downward acceleration (units/s^2); jump height is JUMP_VELOCITY^2 / (2 * GRAVITY)
+pub const GRAVITY: f32 = 20.0;
+// Upward speed (units/s) applied when a jump starts
+pub const JUMP_VELOCITY: f32 = 8.0;
+// Fastest a player can fall (units/s)
+pub const TERMINAL_VELOCITY: f32 = 40.0;
+// Grace period after walking off a ledge during which a jump is still allowed
+pub const COYOTE_TIME_SECONDS: f32 = 0.12;
+// Grounded players follow the terrain down as long as it drops less than this in one step
+pub const GROUND_SNAP_DISTANCE: f32 = 0.5;
+
+// --- Mana ---
+
+// Mana restored to every living player once per regen interval
+pub const MANA_REGEN_PER_INTERVAL: i32 = 5;
+pub const MANA_REGEN_INTERVAL_SECONDS: f32 = 1.0;
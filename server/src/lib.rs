@@ -4,16 +4,32 @@
  * Main entry point for the SpacetimeDB module. This file contains:
  * 
  * 1. Database Schema:
- *    - PlayerData: Active player information
- *    - LoggedOutPlayerData: Persistent data for disconnected players
+ *    - PlayerData: Active player information (one character per identity in the world)
+ *    - LoggedOutPlayerData: Saved state of characters not in the world, keyed by character_id
+ *    - Character: Per-identity character roster (defined in characters.rs)
+ *    - InputAck: Last processed input sequence and authoritative state per player
  *    - GameTickSchedule: Periodic update scheduling
+ *    - GameTickState: Last tick time, tick count and mana regeneration time
+ *    - ServerMetrics/MetricsWindow: Performance metrics (defined in metrics.rs)
  * 
  * 2. Reducer Functions (Server Endpoints):
- *    - init: Module initialization and game tick scheduling
+ *    - init: Module initialization, game tick and metrics scheduling
  *    - identity_connected/disconnected: Connection lifecycle management
- *    - register_player: Player registration with username and character class
- *    - update_player_input: Processes player movement and state updates
- *    - game_tick: Periodic update for game state (scheduled)
+ *    - create_character: Adds a character (name, class, optional palette color) to the caller's roster
+ *    - delete_character: Removes one of the caller's characters and its saved state
+ *    - select_character: Enters the world as a roster character
+ *    - delete_account: Deletes every character and profile owned by the caller
+ *    - send_global_message / send_proximity_message / send_whisper: Chat channels
+ *    - report_message: Reports a chat message to moderators
+ *    - mute_player / unmute_player / add_filtered_word / remove_filtered_word / resolve_report:
+ *      Moderator-only chat moderation
+ *    - admin_*: Admin-only kick, ban, teleport, heal, set health/mana, reset, role management,
+ *      reserved names, rate limit budgets, movement validation thresholds, obstacle editing and
+ *      world bounds
+ *    - update_player_input: Integrates player input server-side (client position is only a hint)
+ *    - update_profile: Rate-limited rename / color change for the caller's profile
+ *    - cast_spell: Starts casting a specific spell, optionally at a target
+ *    - game_tick: Fixed-rate simulation step using measured delta time (scheduled)
  * 
  * 3. Table Structure:
  *    - All tables use Identity as primary keys where appropriate
@@ -23,25 +39,66 @@
  *    - Table changes require regenerating TypeScript bindings
  *    - Add `public` tag to tables that need client access
  *    - New reducers should follow naming convention and error handling patterns
+ *    - Client-callable reducers run through rate_limit::run_limited; dropped and rejected calls
+ *      return Ok so the spent token is kept (rejections are reported in rejected_call)
+ *    - Every Vector3 argument must pass bounds::require_finite before it is used
  *    - Game logic should be placed in separate modules (like player_logic.rs)
  *    - Extend game_tick for gameplay systems that need periodic updates
  * 
  * Related files:
  *    - common.rs: Shared data structures used in table definitions
  *    - player_logic.rs: Player movement and state update calculations
+ *    - metrics.rs: Server metrics tables and scheduled aggregation
+ *    - validation.rs: Movement plausibility checks and violation tracking
+ *    - animation.rs: Server-side animation state machine
+ *    - combat.rs: Melee attack resolution and combat events
+ *    - spells.rs: Spell definitions, casting, interruption and mana regeneration
+ *    - spawn.rs: Spawn points, death and respawn timers
+ *    - classes.rs: Character class definitions and per-class stats
+ *    - usernames.rs: Username rules, uniqueness and reserved names
+ *    - profiles.rs: Account-level profiles and name history
+ *    - palette.rs: Selectable player colors and least-used color assignment
+ *    - characters.rs: Character roster and entering the world
+ *    - retention.rs: Account deletion and scheduled cleanup of stale saved characters
+ *    - chat.rs: Chat channels, rate limiting and scheduled message pruning
+ *    - moderation.rs: Word filter, mutes, reports and the moderation log
+ *    - admin.rs: Staff roles, bans and privileged reducers with an audit log
+ *    - rate_limit.rs: Per-identity token buckets for client-callable reducers
+ *    - terrain.rs: Chunked heightmap, ground height sampling and slope limits
+ *    - collision.rs: Obstacle geometry, broadphase grid, collide-and-slide and player separation
+ *    - bounds.rs: World bounds, finite-vector checks and out-of-bounds recovery
  */
 
 // Declare modules
 mod common;
 mod player_logic;
 mod metrics;
+mod validation;
+mod animation;
+mod combat;
+mod spells;
+mod spawn;
+mod classes;
+mod usernames;
+mod profiles;
+mod palette;
+mod characters;
+mod retention;
+mod chat;
+mod moderation;
+mod admin;
+mod rate_limit;
+mod terrain;
+mod collision;
+mod bounds;
 
 use spacetimedb::{ReducerContext, Identity, Table, Timestamp, ScheduleAt};
 use std::time::Duration; // Import standard Duration
 
 // Use items from common module (structs are needed for table definitions)
-use crate::common::{Vector3, InputState};
-use metrics::{ServerMetrics, MetricsWindow};
+use crate::common::{Vector3, InputState, AnimationState, SERVER_AUTHORITATIVE_MOVEMENT, SIMULATION_TICK_RATE_HZ, MANA_REGEN_INTERVAL_SECONDS};
+use crate::characters::character_roster;
+use crate::spells::active_cast;
 
 // --- Schema Definitions ---
 
@@ -50,6 +107,7 @@ use metrics::{ServerMetrics, MetricsWindow};
 pub struct PlayerData {
     #[primary_key]
     identity: Identity,
+    character_id: u64,
     username: String,
     character_class: String,
     position: Vector3,
@@ -59,19 +117,39 @@ pub struct PlayerData {
     mana: i32,
     max_mana: i32,
     current_animation: String,
+    animation_state: AnimationState,
+    animation_started_at: Timestamp,
+    // Seconds the current one-shot animation holds (see animation.rs)
+    animation_duration: f32,
     is_moving: bool,
     is_running: bool,
+    // Vertical physics state (see player_logic::step_vertical)
+    is_grounded: bool,
+    vertical_velocity: f32,
+    airborne_seconds: f32,
+    // Set when jump is pressed, consumed by the next simulation step
+    jump_requested: bool,
+    // Horizontal offset of position from the server-integrated position, from the last accepted
+    // client hint (see player_logic::update_input_state)
+    hint_offset: Vector3,
     is_attacking: bool,
     is_casting: bool,
     last_input_seq: u32,
     input: InputState,
     color: String,
+    last_update: Timestamp,
+    last_attack_at: Timestamp,
+    is_dead: bool,
+    respawn_at: Timestamp,
+    team: u32,
 }
 
 #[spacetimedb::table(name = logged_out_player)]
 #[derive(Clone)]
 pub struct LoggedOutPlayerData {
     #[primary_key]
+    character_id: u64,
+    #[index(btree)]
     identity: Identity,
     username: String,
     character_class: String,
@@ -81,9 +159,25 @@ pub struct LoggedOutPlayerData {
     max_health: i32,
     mana: i32,
     max_mana: i32,
+    color: String,
     last_seen: Timestamp,
 }
 
+// Acknowledges the last input the server processed for each player, along with the
+// authoritative state at that sequence, so clients can rewind and replay pending inputs.
+#[spacetimedb::table(name = input_ack, public)]
+#[derive(Clone)]
+pub struct InputAck {
+    #[primary_key]
+    identity: Identity,
+    last_processed_seq: u32,
+    position: Vector3,
+    rotation: Vector3,
+    velocity: Vector3,
+    dropped_inputs: u32,
+    acked_at: Timestamp,
+}
+
 #[spacetimedb::table(name = game_tick_schedule, public, scheduled(game_tick))]
 pub struct GameTickSchedule {
     #[primary_key]
@@ -92,14 +186,26 @@ pub struct GameTickSchedule {
     scheduled_at: ScheduleAt,
 }
 
+// Singleton tracking when the tick last ran. Simulation time is kept per player
+// (PlayerData.last_update, see player_logic::take_fixed_steps), not here.
+#[spacetimedb::table(name = game_tick_state)]
+#[derive(Clone)]
+pub struct GameTickState {
+    #[primary_key]
+    id: u32,
+    last_tick: Timestamp,
+    tick_count: u64,
+    last_mana_regen: Timestamp,
+}
+
 // --- Lifecycle Reducers ---
 
 #[spacetimedb::reducer(init)]
 pub fn init(ctx: &ReducerContext) -> Result<(), String> {
     spacetimedb::log::info!("[INIT] Initializing Vibe Multiplayer module...");
     if ctx.db.game_tick_schedule().count() == 0 {
-        spacetimedb::log::info!("[INIT] Scheduling initial game tick (every 1 second)...");
-        let loop_duration = Duration::from_secs(1);
+        spacetimedb::log::info!("[INIT] Scheduling game tick at {} Hz...", SIMULATION_TICK_RATE_HZ);
+        let loop_duration = Duration::from_secs_f64(1.0 / SIMULATION_TICK_RATE_HZ as f64);
         let schedule = GameTickSchedule {
             scheduled_id: 0,
             scheduled_at: ScheduleAt::Interval(loop_duration.into()),
@@ -111,13 +217,41 @@ pub fn init(ctx: &ReducerContext) -> Result<(), String> {
     } else {
         spacetimedb::log::info!("[INIT] Game tick already scheduled.");
     }
+    if ctx.db.game_tick_state().id().find(0).is_none() {
+        ctx.db.game_tick_state().insert(GameTickState {
+            id: 0,
+            last_tick: ctx.timestamp,
+            tick_count: 0,
+            last_mana_regen: ctx.timestamp,
+        });
+    }
+    metrics::init_metrics(ctx);
+    classes::init_classes(ctx);
+    usernames::init_reserved_names(ctx);
+    spells::init_spells(ctx);
+    spawn::init_spawns(ctx);
+    palette::init_palette(ctx);
+    retention::init_retention(ctx);
+    chat::init_chat(ctx);
+    admin::init_admin(ctx);
+    rate_limit::init_rate_limits(ctx);
+    validation::init_validation(ctx);
+    terrain::init_terrain(ctx);
+    collision::init_collision(ctx);
+    bounds::init_world_bounds(ctx);
     Ok(())
 }
 
 #[spacetimedb::reducer(client_connected)]
-pub fn identity_connected(ctx: &ReducerContext) {
+pub fn identity_connected(ctx: &ReducerContext) -> Result<(), String> {
     spacetimedb::log::info!("Client connected: {}", ctx.sender);
-    // Player registration/re-joining happens in register_player reducer called by client
+    // Returning an error refuses the connection
+    if let Some(ban) = admin::active_ban(ctx, ctx.sender) {
+        spacetimedb::log::info!("Refusing banned identity {}.", ctx.sender);
+        return Err(admin::ban_message(ctx, &ban));
+    }
+    // Characters enter the world through the select_character reducer called by client
+    Ok(())
 }
 
 #[spacetimedb::reducer(client_disconnected)]
@@ -127,137 +261,428 @@ pub fn identity_disconnected(ctx: &ReducerContext) {
     let logout_time: Timestamp = ctx.timestamp;
 
     if let Some(player) = ctx.db.player().identity().find(player_identity) {
-        spacetimedb::log::info!("Moving player {} to logged_out_player table.", player_identity);
-        let logged_out_player = LoggedOutPlayerData {
-            identity: player.identity,
-            username: player.username.clone(),
-            character_class: player.character_class.clone(),
-            position: player.position.clone(),
-            rotation: player.rotation.clone(),
-            health: player.health,
-            max_health: player.max_health,
-            mana: player.mana,
-            max_mana: player.max_mana,
-            last_seen: logout_time,
-        };
-        ctx.db.logged_out_player().insert(logged_out_player);
-        ctx.db.player().identity().delete(player_identity);
+        move_to_logged_out(ctx, player, logout_time);
     } else {
-        spacetimedb::log::warn!("Disconnect by player {} not found in active player table.", player_identity);
-        if let Some(mut logged_out_player) = ctx.db.logged_out_player().identity().find(player_identity) {
-            logged_out_player.last_seen = logout_time;
-            ctx.db.logged_out_player().identity().update(logged_out_player);
-            spacetimedb::log::warn!("Updated last_seen for already logged out player {}.", player_identity);
-        }
+        // Still in character selection; nothing to save
+        spacetimedb::log::info!("Disconnect by {} without a character in the world.", player_identity);
     }
 }
 
 // --- Game Specific Reducers ---
 
 #[spacetimedb::reducer]
-pub fn register_player(ctx: &ReducerContext, username: String, character_class: String) {
-    let player_identity: Identity = ctx.sender;
-    spacetimedb::log::info!(
-        "Registering player {} ({}) with class {}",
-        username,
-        player_identity,
-        character_class
-    );
-
-    if ctx.db.player().identity().find(player_identity).is_some() {
-        spacetimedb::log::warn!("Player {} is already active.", player_identity);
-        return;
-    }
+pub fn create_character(ctx: &ReducerContext, name: String, character_class: String, color: Option<String>) -> Result<(), String> {
+    rate_limit::run_limited(ctx, rate_limit::BUDGET_ACTION, "create_character", || {
+        characters::create_character(ctx, name, character_class, color).map(|_| ())
+    })
+}
 
-    // Assign color and position based on current player count
-    let player_count = ctx.db.player().iter().count();
-    let colors = ["cyan", "magenta", "yellow", "lightgreen", "white", "orange"];
-    let assigned_color = colors[player_count % colors.len()].to_string();
-    // Simple horizontal offset for spawning, start Y at 1.0
-    let spawn_position = Vector3 { x: (player_count as f32 * 5.0) - 2.5, y: 1.0, z: 0.0 };
+#[spacetimedb::reducer]
+pub fn delete_character(ctx: &ReducerContext, character_id: u64) -> Result<(), String> {
+    rate_limit::run_limited(ctx, rate_limit::BUDGET_ACTION, "delete_character", || characters::delete_character(ctx, character_id))
+}
 
-    if let Some(logged_out_player) = ctx.db.logged_out_player().identity().find(player_identity) {
-        spacetimedb::log::info!("Player {} is rejoining.", player_identity);
-        let default_input = InputState {
-            forward: false, backward: false, left: false, right: false,
-            sprint: false, jump: false, attack: false, cast_spell: false,
-            sequence: 0
-        };
-        let rejoining_player = PlayerData {
-            identity: logged_out_player.identity,
-            username: logged_out_player.username.clone(),
-            character_class: logged_out_player.character_class.clone(),
-            position: spawn_position,
-            rotation: logged_out_player.rotation.clone(),
-            health: logged_out_player.health,
-            max_health: logged_out_player.max_health,
-            mana: logged_out_player.mana,
-            max_mana: logged_out_player.max_mana,
-            current_animation: "idle".to_string(),
-            is_moving: false,
-            is_running: false,
-            is_attacking: false,
-            is_casting: false,
-            last_input_seq: 0,
-            input: default_input,
-            color: assigned_color,
-        };
-        ctx.db.player().insert(rejoining_player);
-        ctx.db.logged_out_player().identity().delete(player_identity);
-    } else {
-        spacetimedb::log::info!("Registering new player {}.", player_identity);
-        let default_input = InputState {
-            forward: false, backward: false, left: false, right: false,
-            sprint: false, jump: false, attack: false, cast_spell: false,
-            sequence: 0
-        };
-        ctx.db.player().insert(PlayerData {
-            identity: player_identity,
-            username,
-            character_class,
-            position: spawn_position,
-            rotation: Vector3 { x: 0.0, y: 0.0, z: 0.0 },
-            health: 100,
-            max_health: 100,
-            mana: 100,
-            max_mana: 100,
-            current_animation: "idle".to_string(),
-            is_moving: false,
-            is_running: false,
-            is_attacking: false,
-            is_casting: false,
-            last_input_seq: 0,
-            input: default_input,
-            color: assigned_color,
-        });
-    }
+#[spacetimedb::reducer]
+pub fn select_character(ctx: &ReducerContext, character_id: u64) -> Result<(), String> {
+    rate_limit::run_limited(ctx, rate_limit::BUDGET_ACTION, "select_character", || characters::enter_world(ctx, character_id))
 }
 
 #[spacetimedb::reducer]
-pub fn update_player_input(ctx: &ReducerContext, input: InputState, position: Vector3, rotation: Vector3, animation: String) {
+pub fn delete_account(ctx: &ReducerContext) -> Result<(), String> {
+    rate_limit::run_limited(ctx, rate_limit::BUDGET_ACTION, "delete_account", || retention::delete_account(ctx))
+}
+
+#[spacetimedb::reducer]
+pub fn update_player_input(ctx: &ReducerContext, input: InputState, position: Vector3, rotation: Vector3) {
+    if !rate_limit::allow(ctx, rate_limit::BUDGET_INPUT) {
+        return;
+    }
+    // A NaN or infinite vector would corrupt the player row permanently
+    if let Err(e) = bounds::require_finite(&position, "position").and_then(|_| bounds::require_finite(&rotation, "rotation")) {
+        spacetimedb::log::warn!("Dropping input from {}: {}", ctx.sender, e);
+        return;
+    }
     let start_time = ctx.timestamp;
     
-    // Existing player update logic
-    if let Some(mut player) = ctx.db.player().filter_by_identity(&ctx.sender) {
-        player.position = position;
-        player.rotation = rotation;
-        player.current_animation = animation;
-        player.last_update = ctx.timestamp;
-        ctx.db.player().update_by_identity(player);
+    if let Some(mut player) = ctx.db.player().identity().find(ctx.sender) {
+        // Reject stale, replayed or duplicate inputs
+        if !player_logic::is_newer_sequence(input.sequence, player.last_input_seq) {
+            spacetimedb::log::debug!(
+                "Dropping stale input {} from {} (last processed {})",
+                input.sequence,
+                ctx.sender,
+                player.last_input_seq
+            );
+            if let Some(mut ack) = ctx.db.input_ack().identity().find(ctx.sender) {
+                ack.dropped_inputs = ack.dropped_inputs.saturating_add(1);
+                ctx.db.input_ack().identity().update(ack);
+            }
+            return;
+        }
+
+        // Dead players can't act; only acknowledge the sequence until they respawn
+        if player.is_dead {
+            player.last_input_seq = input.sequence;
+            let player = ctx.db.player().identity().update(player);
+            publish_input_ack(ctx, &player);
+            return;
+        }
+
+        let class = classes::class_stats(ctx, &player.character_class);
+        let terrain = terrain::Terrain::new(ctx);
+        let collision = collision::CollisionWorld::new(ctx);
+        let previous_position = player.position.clone();
+        // Time covered by this update; validation allows movement for exactly this long
+        let elapsed = if SERVER_AUTHORITATIVE_MOVEMENT {
+            // Advances last_update by whole fixed steps, keeping the remainder for later
+            player_logic::update_input_state(&terrain, &collision, &mut player, input, position, rotation, class.speed_multiplier, ctx.timestamp)
+        } else {
+            let elapsed = player_logic::elapsed_seconds(player.last_update, ctx.timestamp);
+            player.position = position;
+            player.rotation = rotation;
+            player.last_update = ctx.timestamp;
+            player_logic::apply_input(&mut player, input, ctx.timestamp);
+            elapsed
+        };
+
+        // Never let the resulting position exceed what is physically plausible
+        let (validated_position, violated) = validation::validate_movement(ctx, ctx.sender, &previous_position, player.position.clone(), elapsed, class.speed_multiplier);
+        player.position = validated_position;
+        // Players never end up inside obstacles and always stand on the ground, whichever
+        // mode produced the position
+        player.position = collision.slide(&previous_position, &player.position);
+        player_logic::settle_on_terrain(&terrain, &mut player);
+        if !bounds::world_bounds(ctx).contains(&player.position) {
+            bounds::recover_player(ctx, &mut player, "update_player_input");
+        }
+        // Only a violation recorded by this move counts; wall slides and terrain snaps don't
+        if violated && validation::should_kick(ctx, ctx.sender) {
+            spacetimedb::log::warn!("Kicking player {} for repeated movement violations.", ctx.sender);
+            validation::record_kick(ctx, ctx.sender);
+            move_to_logged_out(ctx, player, ctx.timestamp);
+            return;
+        }
+
+        if player.is_moving {
+            spells::interrupt_cast(ctx, &mut player, "moved");
+        }
+        if player.input.attack {
+            combat::try_melee_attack(ctx, &mut player);
+        } else if player.input.cast_spell {
+            if let Err(e) = spells::begin_cast(ctx, &mut player, class.default_spell_id, None) {
+                spacetimedb::log::debug!("Cast by {} rejected: {}", ctx.sender, e);
+            }
+        }
+
+        let player = ctx.db.player().identity().update(player);
+        publish_input_ack(ctx, &player);
     }
 
     // Record metrics for this update
     let end_time = ctx.timestamp;
-    let update_time_ms = (end_time.micros() - start_time.micros()) as f32 / 1000.0;
+    let update_time_ms = end_time
+        .duration_since(start_time)
+        .map(|d| d.as_secs_f32() * 1000.0)
+        .unwrap_or(0.0);
     metrics::record_update_metrics(ctx, update_time_ms);
 }
 
-#[spacetimedb::reducer(update)]
-pub fn game_tick(ctx: &ReducerContext, _tick_info: GameTickSchedule) {
-    // Just use a simple log message without timestamp conversion
-    let delta_time = 1.0; // Fixed 1-second tick for simplicity
-    
-    player_logic::update_players_logic(ctx, delta_time);
-    
-    spacetimedb::log::debug!("Game tick completed");
+#[spacetimedb::reducer]
+pub fn update_profile(ctx: &ReducerContext, username: Option<String>, color: Option<String>) -> Result<(), String> {
+    rate_limit::run_limited(ctx, rate_limit::BUDGET_ACTION, "update_profile", || profiles::update_profile(ctx, username, color))
+}
+
+#[spacetimedb::reducer]
+pub fn send_global_message(ctx: &ReducerContext, text: String) -> Result<(), String> {
+    rate_limit::run_limited(ctx, rate_limit::BUDGET_ACTION, "send_global_message", || chat::send_global(ctx, text))
+}
+
+#[spacetimedb::reducer]
+pub fn send_proximity_message(ctx: &ReducerContext, text: String) -> Result<(), String> {
+    rate_limit::run_limited(ctx, rate_limit::BUDGET_ACTION, "send_proximity_message", || chat::send_proximity(ctx, text))
+}
+
+#[spacetimedb::reducer]
+pub fn send_whisper(ctx: &ReducerContext, target_name: String, text: String) -> Result<(), String> {
+    rate_limit::run_limited(ctx, rate_limit::BUDGET_ACTION, "send_whisper", || chat::send_whisper(ctx, target_name, text))
+}
+
+#[spacetimedb::reducer]
+pub fn report_message(ctx: &ReducerContext, message_id: u64, reason: String) -> Result<(), String> {
+    rate_limit::run_limited(ctx, rate_limit::BUDGET_ACTION, "report_message", || moderation::report_message(ctx, message_id, reason))
+}
+
+#[spacetimedb::reducer]
+pub fn mute_player(ctx: &ReducerContext, target: Identity, duration_seconds: u32, reason: String) -> Result<(), String> {
+    moderation::mute_player(ctx, target, duration_seconds, reason)
+}
+
+#[spacetimedb::reducer]
+pub fn unmute_player(ctx: &ReducerContext, target: Identity) -> Result<(), String> {
+    moderation::unmute_player(ctx, target)
+}
+
+#[spacetimedb::reducer]
+pub fn add_filtered_word(ctx: &ReducerContext, word: String, block: bool) -> Result<(), String> {
+    moderation::add_filtered_word(ctx, word, block)
+}
+
+#[spacetimedb::reducer]
+pub fn remove_filtered_word(ctx: &ReducerContext, word: String) -> Result<(), String> {
+    moderation::remove_filtered_word(ctx, word)
+}
+
+#[spacetimedb::reducer]
+pub fn resolve_report(ctx: &ReducerContext, report_id: u64, note: String) -> Result<(), String> {
+    moderation::resolve_report(ctx, report_id, note)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_grant_role(ctx: &ReducerContext, target: Identity, role: admin::Role) -> Result<(), String> {
+    admin::grant_role(ctx, target, role)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_revoke_role(ctx: &ReducerContext, target: Identity) -> Result<(), String> {
+    admin::revoke_role(ctx, target)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_kick(ctx: &ReducerContext, target: Identity, reason: String) -> Result<(), String> {
+    admin::kick(ctx, target, reason)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_ban(ctx: &ReducerContext, target: Identity, reason: String, duration_seconds: Option<u32>) -> Result<(), String> {
+    admin::ban(ctx, target, reason, duration_seconds)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_unban(ctx: &ReducerContext, target: Identity) -> Result<(), String> {
+    admin::unban(ctx, target)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_teleport(ctx: &ReducerContext, target: Identity, position: Vector3) -> Result<(), String> {
+    admin::teleport(ctx, target, position)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_heal(ctx: &ReducerContext, target: Identity) -> Result<(), String> {
+    admin::heal(ctx, target)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_set_health(ctx: &ReducerContext, target: Identity, health: i32) -> Result<(), String> {
+    admin::set_health(ctx, target, health)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_set_mana(ctx: &ReducerContext, target: Identity, mana: i32) -> Result<(), String> {
+    admin::set_mana(ctx, target, mana)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_reset_player(ctx: &ReducerContext, target: Identity) -> Result<(), String> {
+    admin::reset(ctx, target)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_add_reserved_name(ctx: &ReducerContext, name: String, match_substring: bool, reason: String) -> Result<(), String> {
+    usernames::add_reserved_name(ctx, name, match_substring, reason)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_remove_reserved_name(ctx: &ReducerContext, name: String) -> Result<(), String> {
+    usernames::remove_reserved_name(ctx, name)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_set_rate_limit(ctx: &ReducerContext, budget: String, capacity: f32, refill_per_second: f32) -> Result<(), String> {
+    rate_limit::set_budget(ctx, budget, capacity, refill_per_second)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_set_movement_validation(
+    ctx: &ReducerContext,
+    teleport_distance_factor: f32,
+    violation_window_seconds: u32,
+    violation_kick_threshold: u32,
+    kick_cooldown_seconds: u32,
+) -> Result<(), String> {
+    validation::set_config(ctx, teleport_distance_factor, violation_window_seconds, violation_kick_threshold, kick_cooldown_seconds)
+}
+
+// Adds a static obstacle; only the fields used by `shape` matter (see collision.rs)
+#[spacetimedb::reducer]
+pub fn admin_add_obstacle(
+    ctx: &ReducerContext,
+    shape: collision::ObstacleShape,
+    center: Vector3,
+    half_extents: Vector3,
+    radius: f32,
+    height: f32,
+) -> Result<(), String> {
+    collision::add_obstacle(ctx, shape, center, half_extents, radius, height)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_remove_obstacle(ctx: &ReducerContext, obstacle_id: u64) -> Result<(), String> {
+    collision::remove_obstacle(ctx, obstacle_id)
+}
+
+#[spacetimedb::reducer]
+pub fn admin_set_world_bounds(ctx: &ReducerContext, min: Vector3, max: Vector3) -> Result<(), String> {
+    bounds::set_world_bounds(ctx, min, max)
+}
+
+#[spacetimedb::reducer]
+pub fn cast_spell(ctx: &ReducerContext, spell_id: u32, target: Option<Identity>) -> Result<(), String> {
+    rate_limit::run_limited(ctx, rate_limit::BUDGET_ACTION, "cast_spell", || {
+        let mut player = ctx.db.player().identity().find(ctx.sender)
+            .ok_or_else(|| "Player not found".to_string())?;
+        spells::begin_cast(ctx, &mut player, spell_id, target)?;
+        ctx.db.player().identity().update(player);
+        Ok(())
+    })
+}
+
+// --- Helpers ---
+
+// Saves an active player's character into logged_out_player (used on disconnect, kicks
+// and character switches)
+fn move_to_logged_out(ctx: &ReducerContext, player: PlayerData, logout_time: Timestamp) {
+    let player_identity = player.identity;
+    spacetimedb::log::info!("Moving player {} to logged_out_player table.", player_identity);
+    let logged_out_player = LoggedOutPlayerData {
+        character_id: player.character_id,
+        identity: player.identity,
+        username: player.username.clone(),
+        character_class: player.character_class.clone(),
+        position: player.position.clone(),
+        rotation: player.rotation.clone(),
+        health: player.health,
+        max_health: player.max_health,
+        mana: player.mana,
+        max_mana: player.max_mana,
+        color: player.color.clone(),
+        last_seen: logout_time,
+    };
+    ctx.db.logged_out_player().insert(logged_out_player);
+    if let Some(mut character) = ctx.db.character_roster().character_id().find(player.character_id) {
+        character.last_seen = logout_time;
+        ctx.db.character_roster().character_id().update(character);
+    }
+    ctx.db.player().identity().delete(player_identity);
+    ctx.db.input_ack().identity().delete(player_identity);
+    ctx.db.active_cast().caster().delete(player_identity);
+}
+
+// Upserts the input acknowledgement row with the player's current authoritative state
+fn publish_input_ack(ctx: &ReducerContext, player: &PlayerData) {
+    let existing = ctx.db.input_ack().identity().find(player.identity);
+    let ack = InputAck {
+        identity: player.identity,
+        last_processed_seq: player.last_input_seq,
+        position: player.position.clone(),
+        rotation: player.rotation.clone(),
+        velocity: Vector3 {
+            y: player.vertical_velocity,
+            ..player_logic::calculate_velocity(
+                &player.rotation,
+                &player.input,
+                classes::class_stats(ctx, &player.character_class).speed_multiplier,
+            )
+        },
+        dropped_inputs: existing.as_ref().map_or(0, |ack| ack.dropped_inputs),
+        acked_at: ctx.timestamp,
+    };
+    if existing.is_some() {
+        ctx.db.input_ack().identity().update(ack);
+    } else {
+        ctx.db.input_ack().insert(ack);
+    }
+}
+
+#[spacetimedb::reducer]
+pub fn game_tick(ctx: &ReducerContext, _tick_info: GameTickSchedule) -> Result<(), String> {
+    // Only the scheduler may advance the simulation
+    if ctx.sender != ctx.identity() {
+        return Err("game_tick may only be invoked by the scheduler".to_string());
+    }
+
+    let fixed_delta = 1.0 / SIMULATION_TICK_RATE_HZ as f32;
+    let mut state = match ctx.db.game_tick_state().id().find(0) {
+        Some(state) => state,
+        None => ctx.db.game_tick_state().insert(GameTickState {
+            id: 0,
+            last_tick: ctx.timestamp,
+            tick_count: 0,
+            last_mana_regen: ctx.timestamp,
+        }),
+    };
+
+    // Each player advances by the whole fixed steps elapsed since their own last_update
+    player_logic::update_players_logic(ctx, fixed_delta);
+    spells::update_casts(ctx);
+    spawn::process_respawns(ctx);
+    combat::prune_combat_events(ctx);
+    validation::prune_violations(ctx);
+
+    let since_regen = ctx.timestamp
+        .duration_since(state.last_mana_regen)
+        .map(|d| d.as_secs_f32())
+        .unwrap_or(0.0);
+    if since_regen >= MANA_REGEN_INTERVAL_SECONDS {
+        spells::regenerate_mana(ctx);
+        state.last_mana_regen = ctx.timestamp;
+    }
+
+    state.last_tick = ctx.timestamp;
+    state.tick_count += 1;
+    spacetimedb::log::debug!("Game tick {} completed", state.tick_count);
+    ctx.db.game_tick_state().id().update(state);
+    Ok(())
+}
+
+// Test fixture: a grounded, idle player at the origin with no input
+#[cfg(test)]
+pub(crate) fn test_player() -> PlayerData {
+    PlayerData {
+        identity: Identity::ZERO,
+        character_id: 1,
+        username: "tester".to_string(),
+        character_class: "Wizard".to_string(),
+        position: Vector3 { x: 0.0, y: 0.0, z: 0.0 },
+        rotation: Vector3 { x: 0.0, y: 0.0, z: 0.0 },
+        health: 100,
+        max_health: 100,
+        mana: 100,
+        max_mana: 100,
+        current_animation: "idle".to_string(),
+        animation_state: AnimationState::Idle,
+        animation_started_at: Timestamp::UNIX_EPOCH,
+        animation_duration: 0.0,
+        is_moving: false,
+        is_running: false,
+        is_grounded: true,
+        vertical_velocity: 0.0,
+        airborne_seconds: 0.0,
+        jump_requested: false,
+        hint_offset: Vector3 { x: 0.0, y: 0.0, z: 0.0 },
+        is_attacking: false,
+        is_casting: false,
+        last_input_seq: 0,
+        input: InputState {
+            forward: false, backward: false, left: false, right: false,
+            sprint: false, jump: false, attack: false, cast_spell: false,
+            sequence: 0
+        },
+        color: "#FFFFFF".to_string(),
+        last_update: Timestamp::UNIX_EPOCH,
+        last_attack_at: Timestamp::UNIX_EPOCH,
+        is_dead: false,
+        respawn_at: Timestamp::UNIX_EPOCH,
+        team: 0,
+    }
 }
@@ -1,73 +1,137 @@
-use spacetimedb::{spacetimedb, Identity, Timestamp};
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - metrics.rs
+ *
+ * Server-side performance metrics used by the stress testing tools.
+ *
+ * Key components:
+ * - MetricsWindow: Rolling 1-second buckets of update counts, update time and rate-limited drops
+ * - ServerMetrics: Periodic aggregated snapshots (public, for StressTestPanel)
+ * - MetricsSchedule: Scheduled table driving update_server_metrics every second
+ *
+ * Flow:
+ * - init_metrics is called from the module's init reducer
+ * - record_update_metrics is called by update_player_input for every update
+ * - record_dropped_call is called by rate_limit.rs for every call it drops
+ * - update_server_metrics aggregates the windows and prunes old snapshots
+ *
+ * Related files:
+ * - lib.rs: Calls init_metrics and record_update_metrics
+ * - rate_limit.rs: Calls record_dropped_call
+ */
+
+use spacetimedb::{ReducerContext, Table, Timestamp, ScheduleAt};
 use std::time::Duration;
-use sysinfo::{System, SystemExt};
+use sysinfo::{ProcessorExt, System, SystemExt};
+
+use crate::player;
 
-#[spacetimedb(table)]
+#[spacetimedb::table(name = server_metrics, public)]
 pub struct ServerMetrics {
-    #[primarykey]
+    #[primary_key]
+    #[auto_inc]
+    pub metrics_id: u64,
     pub timestamp: Timestamp,
     pub connected_clients: i32,
     pub updates_per_second: f32,
     pub average_update_time_ms: f32,
     pub memory_usage_mb: f32,
     pub cpu_usage_percent: f32,
+    pub dropped_calls_per_second: f32,
 }
 
-#[spacetimedb(table)]
+#[spacetimedb::table(name = metrics_window, public)]
 pub struct MetricsWindow {
-    #[primarykey]
+    #[primary_key]
     pub window_id: i32,
     pub start_time: Timestamp,
     pub update_count: i32,
     pub total_update_time_ms: f32,
+    pub dropped_calls: i32,
+}
+
+#[spacetimedb::table(name = metrics_schedule, scheduled(update_server_metrics))]
+pub struct MetricsSchedule {
+    #[primary_key]
+    #[auto_inc]
+    scheduled_id: u64,
+    scheduled_at: ScheduleAt,
 }
 
 // Track the last 60 seconds of metrics in 1-second windows
 const METRICS_WINDOW_COUNT: i32 = 60;
+const WINDOW_MICROS: i64 = 1_000_000;
+const METRICS_RETENTION_MICROS: i64 = METRICS_WINDOW_COUNT as i64 * WINDOW_MICROS;
 
-#[spacetimedb(reducer)]
+// Called from init: creates the rolling windows and schedules aggregation
 pub fn init_metrics(ctx: &ReducerContext) {
-    // Initialize metrics windows
-    for i in 0..METRICS_WINDOW_COUNT {
-        ctx.db.metrics_window().insert(MetricsWindow {
-            window_id: i,
-            start_time: ctx.timestamp,
-            update_count: 0,
-            total_update_time_ms: 0.0,
+    if ctx.db.metrics_window().count() == 0 {
+        for i in 0..METRICS_WINDOW_COUNT {
+            ctx.db.metrics_window().insert(MetricsWindow {
+                window_id: i,
+                start_time: ctx.timestamp,
+                update_count: 0,
+                total_update_time_ms: 0.0,
+                dropped_calls: 0,
+            });
+        }
+    }
+
+    if ctx.db.metrics_schedule().count() == 0 {
+        spacetimedb::log::info!("[INIT] Scheduling server metrics aggregation (every 1 second)...");
+        ctx.db.metrics_schedule().insert(MetricsSchedule {
+            scheduled_id: 0,
+            scheduled_at: ScheduleAt::Interval(Duration::from_secs(1).into()),
         });
     }
 }
 
-#[spacetimedb(reducer)]
 pub fn record_update_metrics(ctx: &ReducerContext, update_time_ms: f32) {
-    let current_window = (ctx.timestamp.micros() / 1_000_000) % (METRICS_WINDOW_COUNT as u64);
-    
-    // Update the current window
-    if let Some(mut window) = ctx.db.metrics_window().filter_by_window_id(current_window as i32) {
-        // If window is from previous cycle, reset it
-        if (ctx.timestamp.micros() - window.start_time.micros()) >= (METRICS_WINDOW_COUNT as u64 * 1_000_000) {
-            window.start_time = ctx.timestamp;
-            window.update_count = 1;
-            window.total_update_time_ms = update_time_ms;
-        } else {
-            window.update_count += 1;
-            window.total_update_time_ms += update_time_ms;
-        }
-        ctx.db.metrics_window().update_by_window_id(window);
+    if let Some(mut window) = current_window(ctx) {
+        window.update_count += 1;
+        window.total_update_time_ms += update_time_ms;
+        ctx.db.metrics_window().window_id().update(window);
     }
 }
 
-#[spacetimedb(reducer(interval_secs = 1))]
-pub fn update_server_metrics(ctx: &ReducerContext) {
+pub fn record_dropped_call(ctx: &ReducerContext) {
+    if let Some(mut window) = current_window(ctx) {
+        window.dropped_calls += 1;
+        ctx.db.metrics_window().window_id().update(window);
+    }
+}
+
+// The window for the current second, reset if it still holds data from the previous cycle
+fn current_window(ctx: &ReducerContext) -> Option<MetricsWindow> {
+    let now_micros = ctx.timestamp.to_micros_since_unix_epoch();
+    let window_id = (now_micros / WINDOW_MICROS).rem_euclid(METRICS_WINDOW_COUNT as i64) as i32;
+    let mut window = ctx.db.metrics_window().window_id().find(window_id)?;
+    if now_micros - window.start_time.to_micros_since_unix_epoch() >= METRICS_RETENTION_MICROS {
+        window.start_time = ctx.timestamp;
+        window.update_count = 0;
+        window.total_update_time_ms = 0.0;
+        window.dropped_calls = 0;
+    }
+    Some(window)
+}
+
+#[spacetimedb::reducer]
+pub fn update_server_metrics(ctx: &ReducerContext, _schedule: MetricsSchedule) -> Result<(), String> {
+    // Only the scheduler may run the aggregation
+    if ctx.sender != ctx.identity() {
+        return Err("update_server_metrics may only be invoked by the scheduler".to_string());
+    }
+
     // Calculate updates per second and average update time
     let mut total_updates = 0;
     let mut total_time = 0.0;
-    let now = ctx.timestamp;
+    let mut total_dropped = 0;
+    let now_micros = ctx.timestamp.to_micros_since_unix_epoch();
 
     for window in ctx.db.metrics_window().iter() {
-        if (now.micros() - window.start_time.micros()) < (METRICS_WINDOW_COUNT as u64 * 1_000_000) {
+        if now_micros - window.start_time.to_micros_since_unix_epoch() < METRICS_RETENTION_MICROS {
             total_updates += window.update_count;
             total_time += window.total_update_time_ms;
+            total_dropped += window.dropped_calls;
         }
     }
 
@@ -86,24 +150,30 @@ pub fn update_server_metrics(ctx: &ReducerContext) {
     system.refresh_all();
 
     // Get memory and CPU usage
-    let total_memory = system.total_memory() as f32 / 1024.0; // Convert to MB
-    let used_memory = system.used_memory() as f32 / 1024.0; // Convert to MB
-    let memory_usage_mb = used_memory;
+    let memory_usage_mb = system.used_memory() as f32 / 1024.0; // Convert to MB
     let cpu_usage_percent = system.global_processor_info().cpu_usage();
 
     // Insert new metrics
     ctx.db.server_metrics().insert(ServerMetrics {
+        metrics_id: 0,
         timestamp: ctx.timestamp,
         connected_clients,
         updates_per_second,
         average_update_time_ms: average_update_time,
         memory_usage_mb,
         cpu_usage_percent,
+        dropped_calls_per_second: total_dropped as f32 / METRICS_WINDOW_COUNT as f32,
     });
 
     // Clean up old metrics
-    let cutoff_time = Timestamp::from_micros(ctx.timestamp.micros() - 60_000_000); // 60 seconds
-    ctx.db.server_metrics()
+    let cutoff_time = Timestamp::from_micros_since_unix_epoch(now_micros - METRICS_RETENTION_MICROS);
+    let expired: Vec<ServerMetrics> = ctx.db.server_metrics()
+        .iter()
         .filter(|m| m.timestamp < cutoff_time)
-        .delete();
-} 
\ No newline at end of file
+        .collect();
+    for metrics in expired {
+        ctx.db.server_metrics().delete(metrics);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,297 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - moderation.rs
+ *
+ * Chat moderation: word filter, mutes, player reports and the moderation log.
+ *
+ * Key components:
+ * - FilteredWord: Words or fragments that are masked with '*' or block the message outright
+ * - ChatMute: Active mutes; muted identities can't send chat until muted_until
+ * - ChatReport: Messages reported by players, with a copy of the text (messages are pruned)
+ * - ModerationAction: Log of every moderation action with the acting identity and time
+ *
+ * When modifying:
+ * - Filtering is case-insensitive and applied in chat.rs before a message is stored
+ * - Every moderator reducer must call admin::require_role(ctx, Role::Moderator) and log_action;
+ *   moderation rights come only from staff roles
+ *
+ * Related files:
+ * - chat.rs: Calls filter_message and check_not_muted when sending
+ * - lib.rs: Moderation and report reducers
+ * - admin.rs: Staff roles, which also grant moderation rights
+ */
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::admin::{self, Role};
+use crate::chat::chat_message;
+
+const MAX_REPORT_REASON_LENGTH: usize = 200;
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum FilterAction {
+    Mask,
+    Block,
+}
+
+#[spacetimedb::table(name = filtered_word)]
+#[derive(Clone)]
+pub struct FilteredWord {
+    // Stored lowercase
+    #[primary_key]
+    pub word: String,
+    pub action: FilterAction,
+}
+
+#[spacetimedb::table(name = chat_mute)]
+#[derive(Clone)]
+pub struct ChatMute {
+    #[primary_key]
+    pub identity: Identity,
+    pub muted_until: Timestamp,
+    pub reason: String,
+    pub muted_by: Identity,
+}
+
+#[spacetimedb::table(name = chat_report)]
+#[derive(Clone)]
+pub struct ChatReport {
+    #[primary_key]
+    #[auto_inc]
+    pub report_id: u64,
+    pub reporter: Identity,
+    pub message_id: u64,
+    #[index(btree)]
+    pub reported: Identity,
+    pub reported_name: String,
+    pub message_text: String,
+    pub reason: String,
+    pub reported_at: Timestamp,
+    pub resolved: bool,
+}
+
+#[spacetimedb::table(name = moderation_action)]
+#[derive(Clone)]
+pub struct ModerationAction {
+    #[primary_key]
+    #[auto_inc]
+    pub action_id: u64,
+    pub actor: Identity,
+    pub action: String,
+    pub target: Option<Identity>,
+    pub details: String,
+    pub timestamp: Timestamp,
+}
+
+pub fn log_action(ctx: &ReducerContext, action: &str, target: Option<Identity>, details: String) {
+    spacetimedb::log::info!("Moderation: {} by {} (target {:?}): {}", action, ctx.sender, target, details);
+    ctx.db.moderation_action().insert(ModerationAction {
+        action_id: 0,
+        actor: ctx.sender,
+        action: action.to_string(),
+        target,
+        details,
+        timestamp: ctx.timestamp,
+    });
+}
+
+// Applies the word filter: masks matching fragments, or rejects the message if a
+// blocked word is present
+pub fn filter_message(ctx: &ReducerContext, text: &str) -> Result<String, String> {
+    let words: Vec<FilteredWord> = ctx.db.filtered_word().iter().collect();
+    apply_filter(text, &words)
+}
+
+// The filter itself. Matching is case-insensitive and done char by char on the original text,
+// so characters whose lowercase form has a different length can't shift the masked range.
+fn apply_filter(text: &str, words: &[FilteredWord]) -> Result<String, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut masked = vec![false; chars.len()];
+    let same_letter = |a: char, b: char| a == b || a.to_lowercase().eq(b.to_lowercase());
+    for entry in words {
+        let word: Vec<char> = entry.word.chars().collect();
+        if word.is_empty() || word.len() > chars.len() {
+            continue;
+        }
+        for start in 0..=chars.len() - word.len() {
+            let matches = chars[start..start + word.len()]
+                .iter()
+                .zip(&word)
+                .all(|(&c, &w)| same_letter(c, w));
+            if !matches {
+                continue;
+            }
+            if entry.action == FilterAction::Block {
+                return Err("Your message contains a blocked word".to_string());
+            }
+            masked[start..start + word.len()].iter_mut().for_each(|m| *m = true);
+        }
+    }
+
+    Ok(chars
+        .into_iter()
+        .zip(masked)
+        .map(|(c, masked)| if masked { '*' } else { c })
+        .collect())
+}
+
+// Fails if `identity` is currently muted
+pub fn check_not_muted(ctx: &ReducerContext, identity: Identity) -> Result<(), String> {
+    match ctx.db.chat_mute().identity().find(identity) {
+        Some(mute) if mute.muted_until > ctx.timestamp => {
+            let remaining = mute.muted_until
+                .duration_since(ctx.timestamp)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            Err(format!("You are muted for another {} seconds ({})", remaining, mute.reason))
+        }
+        Some(_) => {
+            ctx.db.chat_mute().identity().delete(identity);
+            Ok(())
+        }
+        None => Ok(()),
+    }
+}
+
+pub fn mute_player(ctx: &ReducerContext, target: Identity, duration_seconds: u32, reason: String) -> Result<(), String> {
+    admin::require_role(ctx, Role::Moderator)?;
+    if duration_seconds == 0 {
+        return Err("Mute duration must be at least one second".to_string());
+    }
+    let muted_until = Timestamp::from_micros_since_unix_epoch(
+        ctx.timestamp.to_micros_since_unix_epoch() + duration_seconds as i64 * 1_000_000,
+    );
+    let mute = ChatMute { identity: target, muted_until, reason: reason.clone(), muted_by: ctx.sender };
+    if ctx.db.chat_mute().identity().find(target).is_some() {
+        ctx.db.chat_mute().identity().update(mute);
+    } else {
+        ctx.db.chat_mute().insert(mute);
+    }
+    log_action(ctx, "mute", Some(target), format!("{}s: {}", duration_seconds, reason));
+    Ok(())
+}
+
+pub fn unmute_player(ctx: &ReducerContext, target: Identity) -> Result<(), String> {
+    admin::require_role(ctx, Role::Moderator)?;
+    if !ctx.db.chat_mute().identity().delete(target) {
+        return Err("That player is not muted".to_string());
+    }
+    log_action(ctx, "unmute", Some(target), String::new());
+    Ok(())
+}
+
+pub fn add_filtered_word(ctx: &ReducerContext, word: String, block: bool) -> Result<(), String> {
+    admin::require_role(ctx, Role::Moderator)?;
+    let word = word.trim().to_lowercase();
+    if word.is_empty() {
+        return Err("Filtered word can't be empty".to_string());
+    }
+    let entry = FilteredWord {
+        word: word.clone(),
+        action: if block { FilterAction::Block } else { FilterAction::Mask },
+    };
+    if ctx.db.filtered_word().word().find(&word).is_some() {
+        ctx.db.filtered_word().word().update(entry);
+    } else {
+        ctx.db.filtered_word().insert(entry);
+    }
+    log_action(ctx, "add_filtered_word", None, format!("{} ({})", word, if block { "block" } else { "mask" }));
+    Ok(())
+}
+
+pub fn remove_filtered_word(ctx: &ReducerContext, word: String) -> Result<(), String> {
+    admin::require_role(ctx, Role::Moderator)?;
+    let word = word.trim().to_lowercase();
+    if !ctx.db.filtered_word().word().delete(&word) {
+        return Err(format!("'{}' is not filtered", word));
+    }
+    log_action(ctx, "remove_filtered_word", None, word);
+    Ok(())
+}
+
+// Files a report for a chat message the caller could see
+pub fn report_message(ctx: &ReducerContext, message_id: u64, reason: String) -> Result<(), String> {
+    let message = ctx.db.chat_message()
+        .message_id()
+        .find(message_id)
+        .filter(|m| m.recipient.is_none() || m.recipient == Some(ctx.sender))
+        .ok_or_else(|| "Message not found".to_string())?;
+    if message.sender == ctx.sender {
+        return Err("You can't report your own message".to_string());
+    }
+    let already_reported = ctx.db.chat_report()
+        .reported()
+        .filter(&message.sender)
+        .any(|r| r.reporter == ctx.sender && r.message_id == message_id);
+    if already_reported {
+        return Err("You already reported this message".to_string());
+    }
+
+    let reason: String = reason.trim().chars().take(MAX_REPORT_REASON_LENGTH).collect();
+    ctx.db.chat_report().insert(ChatReport {
+        report_id: 0,
+        reporter: ctx.sender,
+        message_id,
+        reported: message.sender,
+        reported_name: message.sender_name,
+        message_text: message.text,
+        reason,
+        reported_at: ctx.timestamp,
+        resolved: false,
+    });
+    spacetimedb::log::info!("Player {} reported message {} from {}", ctx.sender, message_id, message.sender);
+    Ok(())
+}
+
+pub fn resolve_report(ctx: &ReducerContext, report_id: u64, note: String) -> Result<(), String> {
+    admin::require_role(ctx, Role::Moderator)?;
+    let mut report = ctx.db.chat_report()
+        .report_id()
+        .find(report_id)
+        .ok_or_else(|| "Report not found".to_string())?;
+    if report.resolved {
+        return Err("Report is already resolved".to_string());
+    }
+    report.resolved = true;
+    let reported = report.reported;
+    ctx.db.chat_report().report_id().update(report);
+    log_action(ctx, "resolve_report", Some(reported), format!("report {}: {}", report_id, note));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(word: &str, action: FilterAction) -> FilteredWord {
+        FilteredWord { word: word.to_string(), action }
+    }
+
+    #[test]
+    fn masked_words_are_replaced_case_insensitively() {
+        let words = [word("darn", FilterAction::Mask)];
+        assert_eq!(apply_filter("Darn it, DARN", &words), Ok("**** it, ****".to_string()));
+        assert_eq!(apply_filter("nothing here", &words), Ok("nothing here".to_string()));
+    }
+
+    #[test]
+    fn overlapping_matches_are_all_masked() {
+        let words = [word("aa", FilterAction::Mask)];
+        assert_eq!(apply_filter("baaab", &words), Ok("b***b".to_string()));
+    }
+
+    #[test]
+    fn blocked_words_reject_the_message() {
+        let words = [word("darn", FilterAction::Mask), word("heck", FilterAction::Block)];
+        assert!(apply_filter("what the HECK", &words).is_err());
+        assert!(apply_filter("what the darn", &words).is_ok());
+    }
+
+    #[test]
+    fn non_ascii_text_is_masked_in_place() {
+        let words = [word("ärger", FilterAction::Mask)];
+        assert_eq!(apply_filter("ÄRGER und Ärger", &words), Ok("***** und *****".to_string()));
+        // 'İ' lowercases to two chars; the match after it must still line up
+        let words = [word("bad", FilterAction::Mask)];
+        assert_eq!(apply_filter("İBAD", &words), Ok("İ***".to_string()));
+    }
+}
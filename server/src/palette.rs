@@ -0,0 +1,79 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - palette.rs
+ *
+ * Server-defined player color palette.
+ *
+ * Key components:
+ * - PaletteColor: Public table of selectable colors (CSS color value plus display label)
+ * - resolve_color: Validates a requested color (by value, hex or label) against the palette
+ * - least_used_color: Fallback assignment that spreads colors across active players
+ *
+ * When modifying:
+ * - Values must be valid CSS/Three.js colors since the client renders them directly
+ * - Seed defaults in init_palette; the module owner can edit color_palette with `spacetime sql`
+ *
+ * Related files:
+ * - characters.rs: create_character assigns colors
+ * - profiles.rs: update_profile validates color changes
+ */
+
+use spacetimedb::{ReducerContext, Table};
+
+use crate::player;
+
+#[spacetimedb::table(name = color_palette, public)]
+#[derive(Clone)]
+pub struct PaletteColor {
+    #[primary_key]
+    pub value: String,
+    pub label: String,
+    pub sort_order: u32,
+}
+
+// Used if the palette table is empty
+const FALLBACK_COLOR: &str = "white";
+
+// Called from init: seeds the default palette
+pub fn init_palette(ctx: &ReducerContext) {
+    let defaults = [
+        ("cyan", "Cyan"),
+        ("magenta", "Magenta"),
+        ("yellow", "Yellow"),
+        ("lightgreen", "Light Green"),
+        ("white", "White"),
+        ("orange", "Orange"),
+        ("#ff6b6b", "Coral"),
+        ("#4d96ff", "Sky Blue"),
+    ];
+    for (order, (value, label)) in defaults.iter().enumerate() {
+        if ctx.db.color_palette().value().find(&value.to_string()).is_none() {
+            ctx.db.color_palette().insert(PaletteColor {
+                value: value.to_string(),
+                label: label.to_string(),
+                sort_order: order as u32,
+            });
+        }
+    }
+}
+
+// Returns the palette value for a requested color, matching value or label case-insensitively
+pub fn resolve_color(ctx: &ReducerContext, requested: &str) -> Result<String, String> {
+    let requested = requested.trim().to_lowercase();
+    ctx.db.color_palette()
+        .iter()
+        .find(|c| c.value.to_lowercase() == requested || c.label.to_lowercase() == requested)
+        .map(|c| c.value)
+        .ok_or_else(|| format!("'{}' is not an available color", requested))
+}
+
+// The palette color worn by the fewest active players (ties broken by sort order)
+pub fn least_used_color(ctx: &ReducerContext) -> String {
+    let in_use: Vec<String> = ctx.db.player().iter().map(|p| p.color).collect();
+    let mut palette: Vec<PaletteColor> = ctx.db.color_palette().iter().collect();
+    palette.sort_by_key(|c| c.sort_order);
+    palette
+        .into_iter()
+        .min_by_key(|c| in_use.iter().filter(|used| **used == c.value).count())
+        .map(|c| c.value)
+        .unwrap_or_else(|| FALLBACK_COLOR.to_string())
+}
@@ -7,43 +7,76 @@
  * Key components:
  * 
  * 1. Movement Calculation:
- *    - calculate_new_position: Computes player movement based on input and rotation
+ *    - calculate_velocity: Converts input and rotation into a horizontal velocity
+ *    - calculate_new_position: Computes horizontal movement based on input and rotation,
+ *      sliding along obstacles and refusing slopes steeper than the terrain limit
+ *    - step_vertical: Jump impulse, gravity, landing and coyote time for one fixed step
+ *    - simulate_step: One fixed step of horizontal movement followed by vertical physics
+ *    - facing_direction: Forward unit vector for a rotation (used for hit cones)
  *    - Vector math for converting input to movement direction
  *    - Direction normalization and speed application
  * 
  * 2. State Management:
  *    - update_input_state: Updates player state based on client input
- *    - Handles position, animation, and derived state (is_moving, is_running)
+ *    - Integrates input server-side in whole fixed steps; the client position is accepted as a
+ *      hint only within CLIENT_POSITION_TOLERANCE of the integrated position (hint_offset)
+ *    - elapsed_seconds: Real delta time between updates derived from timestamps
+ *    - is_newer_sequence: Wraparound-safe input sequence ordering
+ *    - apply_input: Derives movement flags and drives the animation state machine
+ *    - Handles position, animation, and derived state (is_moving, is_running, is_grounded)
  *    - Translates raw input to game state
  * 
  * 3. Game Tick:
- *    - update_players_logic: Placeholder for periodic player updates
- *    - Currently empty as players are updated directly through input
+ *    - update_players_logic: Advances stored player input by fixed simulation steps
+ *    - take_fixed_steps: Per-player simulation clock; whole steps since last_update, remainder carried
+ *    - Separates overlapping players after moving them
+ *    - Returns players found outside the world bounds to a spawn point
+ *    - Keeps players moving between input packets and expires finished animations
  *    - Can be extended for server-side simulation (AI, physics, etc.)
  * 
+ * Vertical physics:
+ *    - Pressing jump (the false -> true edge of InputState.jump) sets jump_requested; the next
+ *      fixed step jumps if the player is grounded (or within COYOTE_TIME_SECONDS of leaving
+ *      the ground), then they fall under GRAVITY until they land on the terrain
+ *    - Holding jump doesn't jump again on landing
+ *    - Everything is integrated in fixed steps of 1 / SIMULATION_TICK_RATE_HZ, so a client
+ *      predicting with the same constants and step size reproduces the server result
+ * 
  * Extension points:
- *    - Add terrain logic for realistic height adjustments
- *    - Implement server-side animation determination (commented example provided)
- *    - Add collision detection in calculate_new_position
  *    - Expand update_players_logic for server-side gameplay mechanics
  * 
  * Related files:
  *    - common.rs: Provides shared data types and constants
  *    - lib.rs: Calls into this module's functions from reducers
+ *    - terrain.rs: Ground height and slope checks
+ *    - collision.rs: Obstacle collide-and-slide and player separation
+ *    - bounds.rs: World bounds and out-of-bounds recovery
  */
 
-use spacetimedb::ReducerContext;
+use spacetimedb::{ReducerContext, Table, Timestamp};
 // Import common structs and constants
-use crate::common::{Vector3, InputState, PLAYER_SPEED, SPRINT_MULTIPLIER};
-// Import the PlayerData struct definition (assuming it's in lib.rs or common.rs)
-use crate::PlayerData;
+use crate::common::{
+    Vector3, InputState, PLAYER_SPEED, SPRINT_MULTIPLIER, CLIENT_POSITION_TOLERANCE, MAX_INPUT_DELTA_SECONDS,
+    SIMULATION_TICK_RATE_HZ, MAX_CATCH_UP_STEPS, GRAVITY, JUMP_VELOCITY, TERMINAL_VELOCITY, COYOTE_TIME_SECONDS, GROUND_SNAP_DISTANCE,
+};
+use crate::animation;
+use crate::common::AnimationState;
+use crate::bounds;
+use crate::classes;
+use crate::collision::{self, CollisionWorld};
+use crate::terrain::Terrain;
+// Import the PlayerData struct definition and its table accessor from lib.rs
+use crate::{player, PlayerData};
 
+// Horizontal velocity (units/second) produced by an input at a given rotation.
+// speed_multiplier comes from the player's class definition.
 // Corrected movement logic based on reversed feedback
-pub fn calculate_new_position(position: &Vector3, rotation: &Vector3, input: &InputState, delta_time: f32) -> Vector3 {
+pub fn calculate_velocity(rotation: &Vector3, input: &InputState, speed_multiplier: f32) -> Vector3 {
     let has_movement_input = input.forward || input.backward || input.left || input.right;
 
     if has_movement_input {
-        let speed = if input.sprint { PLAYER_SPEED * SPRINT_MULTIPLIER } else { PLAYER_SPEED };
+        let base_speed = PLAYER_SPEED * speed_multiplier;
+        let speed = if input.sprint { base_speed * SPRINT_MULTIPLIER } else { base_speed };
 
         // This approach more directly matches the new client implementation
         // Create basis vectors for movement (forward/right vectors from camera)
@@ -100,66 +133,497 @@ pub fn calculate_new_position(position: &Vector3, rotation: &Vector3, input: &In
             direction.z /= magnitude;
         }
         
-        // Apply speed and delta time
-        direction.x *= speed * delta_time;
-        direction.z *= speed * delta_time;
-        
-        // Create new position
-        let mut new_position = position.clone();
-        new_position.x += direction.x;
-        new_position.z += direction.z;
-        
-        // For terrain, you could implement height logic here if needed
-        // Example: new_position.y = calculate_terrain_height(new_position.x, new_position.z);
-        
-        return new_position;
+        // Apply speed
+        direction.x *= speed;
+        direction.z *= speed;
+
+        direction
+    } else {
+        // No movement input, no velocity
+        Vector3 { x: 0.0, y: 0.0, z: 0.0 }
+    }
+}
+
+// Moves horizontally, sliding along obstacles; a step up a slope steeper than the terrain
+// limit is refused and the player stays where they were. Y is left to step_vertical.
+pub fn calculate_new_position(
+    terrain: &Terrain,
+    collision: &CollisionWorld,
+    position: &Vector3,
+    rotation: &Vector3,
+    input: &InputState,
+    speed_multiplier: f32,
+    delta_time: f32,
+) -> Vector3 {
+    let velocity = calculate_velocity(rotation, input, speed_multiplier);
+
+    // Create new position
+    let mut new_position = position.clone();
+    new_position.x += velocity.x * delta_time;
+    new_position.z += velocity.z * delta_time;
+    let new_position = collision.slide(position, &new_position);
+
+    if !terrain.is_walkable(position, &new_position) {
+        return position.clone();
+    }
+    new_position
+}
+
+// Advances vertical physics by one step of `delta_time` seconds over ground at `ground_height`.
+// A jump starts if `jump` is set (jump was pressed since the last step) and the player is
+// grounded or within coyote time.
+pub fn step_vertical(player: &mut PlayerData, ground_height: f32, jump: bool, delta_time: f32, now: Timestamp) {
+    let can_jump = !player.is_dead && (player.is_grounded || player.airborne_seconds < COYOTE_TIME_SECONDS);
+    if jump && can_jump && player.vertical_velocity <= 0.0 {
+        player.vertical_velocity = JUMP_VELOCITY;
+        player.is_grounded = false;
+        // Use up the coyote time so the jump can't be repeated in mid-air
+        player.airborne_seconds = COYOTE_TIME_SECONDS;
+        animation::play_action(player, AnimationState::Jump, now);
+    }
+
+    // Grounded players follow the terrain down gentle drops instead of falling
+    let height_above_ground = player.position.y - ground_height;
+    if player.is_grounded && player.vertical_velocity <= 0.0 && height_above_ground <= GROUND_SNAP_DISTANCE {
+        player.position.y = ground_height;
+        player.vertical_velocity = 0.0;
+        player.airborne_seconds = 0.0;
+        return;
+    }
+
+    // Semi-implicit Euler: update velocity first, then position
+    player.vertical_velocity = (player.vertical_velocity - GRAVITY * delta_time).max(-TERMINAL_VELOCITY);
+    player.position.y = (player.position.y + player.vertical_velocity * delta_time).max(ground_height);
+    if player.position.y <= ground_height && player.vertical_velocity <= 0.0 {
+        player.vertical_velocity = 0.0;
+        player.is_grounded = true;
+        player.airborne_seconds = 0.0;
+    } else {
+        player.is_grounded = false;
+        player.airborne_seconds += delta_time;
+    }
+}
+
+// One fixed simulation step: horizontal movement from `input`, then vertical physics, consuming
+// any pending jump press
+pub fn simulate_step(
+    terrain: &Terrain,
+    collision: &CollisionWorld,
+    player: &mut PlayerData,
+    rotation: &Vector3,
+    input: &InputState,
+    speed_multiplier: f32,
+    delta_time: f32,
+    now: Timestamp,
+) {
+    player.position = calculate_new_position(terrain, collision, &player.position, rotation, input, speed_multiplier, delta_time);
+    let ground_height = terrain.height_at(player.position.x, player.position.z);
+    // A jump press is used up by the first step after it, whether or not it could jump
+    let jump = std::mem::take(&mut player.jump_requested);
+    step_vertical(player, ground_height, jump, delta_time, now);
+}
+
+// Keeps a player on the terrain after their position was set from outside the simulation:
+// grounded players stand on the ground, airborne players can't end up below it
+pub fn settle_on_terrain(terrain: &Terrain, player: &mut PlayerData) {
+    let ground_height = terrain.height_at(player.position.x, player.position.z);
+    if player.is_grounded || player.position.y < ground_height {
+        player.position.y = ground_height;
+    }
+}
+
+// Clears vertical motion and any accepted client hint, for players placed on the ground by
+// spawning or teleporting
+pub fn reset_vertical(player: &mut PlayerData) {
+    player.hint_offset = Vector3 { x: 0.0, y: 0.0, z: 0.0 };
+    player.jump_requested = false;
+    player.is_grounded = true;
+    player.vertical_velocity = 0.0;
+    player.airborne_seconds = 0.0;
+}
+
+// Unit vector the player faces for a given rotation; same direction as moving "forward"
+pub fn facing_direction(rotation: &Vector3) -> Vector3 {
+    Vector3 { x: rotation.y.sin(), y: 0.0, z: rotation.y.cos() }
+}
+
+// True if `sequence` comes after `last` in u32 sequence space, tolerating wraparound.
+// Sequences more than half the range behind are considered stale.
+pub fn is_newer_sequence(sequence: u32, last: u32) -> bool {
+    let diff = sequence.wrapping_sub(last);
+    diff != 0 && diff < (1u32 << 31)
+}
+
+// Splits `elapsed_micros` into whole fixed simulation steps and the microseconds they cover.
+// Beyond `max_steps` the extra time is dropped: all of it is reported as covered.
+pub fn split_fixed_steps(elapsed_micros: i64, max_steps: u32) -> (u32, i64) {
+    let step_micros = 1_000_000 / SIMULATION_TICK_RATE_HZ as i64;
+    let steps = elapsed_micros.max(0) / step_micros;
+    if steps > max_steps as i64 {
+        (max_steps, elapsed_micros)
+    } else {
+        (steps as u32, steps * step_micros)
+    }
+}
+
+// Takes the whole fixed steps elapsed since the player was last simulated (at most `max_steps`)
+// and advances last_update past them; leftover time carries over to the next call
+pub fn take_fixed_steps(player: &mut PlayerData, now: Timestamp, max_steps: u32) -> u32 {
+    let last_update = player.last_update.to_micros_since_unix_epoch();
+    let (steps, covered) = split_fixed_steps(now.to_micros_since_unix_epoch() - last_update, max_steps);
+    player.last_update = Timestamp::from_micros_since_unix_epoch(last_update + covered);
+    steps
+}
+
+// Moves the player back to their server-integrated position and returns the hint offset
+// that was removed
+fn take_hint_offset(player: &mut PlayerData) -> Vector3 {
+    let offset = std::mem::replace(&mut player.hint_offset, Vector3 { x: 0.0, y: 0.0, z: 0.0 });
+    player.position.x -= offset.x;
+    player.position.z -= offset.z;
+    offset
+}
+
+// Shows the player at an accepted client hint; `offset` is horizontal and within
+// CLIENT_POSITION_TOLERANCE of the integrated position
+fn apply_hint_offset(player: &mut PlayerData, offset: Vector3) {
+    player.position.x += offset.x;
+    player.position.z += offset.z;
+    player.hint_offset = offset;
+}
+
+// Seconds elapsed between two timestamps, clamped so a stalled client can't integrate a huge step
+pub fn elapsed_seconds(from: Timestamp, to: Timestamp) -> f32 {
+    let elapsed = to.duration_since(from).map(|d| d.as_secs_f32()).unwrap_or(0.0);
+    elapsed.min(MAX_INPUT_DELTA_SECONDS)
+}
+
+// Update player state based on input (server-authoritative)
+// The whole fixed steps elapsed since the player was last simulated are integrated first, with
+// the input and rotation that were held during that time; the new input takes effect from now
+// on. Leftover time carries over, so the result doesn't depend on when packets arrive.
+// Returns the simulated time in seconds.
+// The client-reported position is a hint: within CLIENT_POSITION_TOLERANCE (horizontally) of
+// the integrated position the player is shown there, further away it is only logged and the
+// client is corrected through the input ack. Integration always continues from the server's
+// own position (see take_hint_offset), so the tolerance can't compound into extra speed.
+pub fn update_input_state(
+    terrain: &Terrain,
+    collision: &CollisionWorld,
+    player: &mut PlayerData,
+    input: InputState,
+    client_pos: Vector3,
+    client_rot: Vector3,
+    speed_multiplier: f32,
+    now: Timestamp,
+) -> f32 {
+    let fixed_step = 1.0 / SIMULATION_TICK_RATE_HZ as f32;
+    let max_steps = (MAX_INPUT_DELTA_SECONDS * SIMULATION_TICK_RATE_HZ as f32) as u32;
+    let steps = take_fixed_steps(player, now, max_steps);
+    // The previous hint is dropped; the new one is measured against the integrated position
+    take_hint_offset(player);
+    let (rotation, held_input) = (player.rotation.clone(), player.input.clone());
+    for _ in 0..steps {
+        simulate_step(terrain, collision, player, &rotation, &held_input, speed_multiplier, fixed_step, now);
+    }
+
+    let dx = client_pos.x - player.position.x;
+    let dz = client_pos.z - player.position.z;
+    let drift = (dx * dx + dz * dz).sqrt();
+    if drift <= CLIENT_POSITION_TOLERANCE {
+        apply_hint_offset(player, Vector3 { x: dx, y: 0.0, z: dz });
     } else {
-        // No movement input, return current position
-        position.clone()
-    }
-}
-
-// Note: Animation determination is currently handled client-side
-// You could implement server-side animation logic here if needed
-// For example:
-// pub fn determine_animation(input: &InputState) -> String {
-//     let is_moving = input.forward || input.backward || input.left || input.right;
-//     if input.attack { return "attack1".to_string(); }
-//     if input.jump { return "jump".to_string(); }
-//     if is_moving {
-//         if input.sprint { "run-forward".to_string() }
-//         else { "walk-forward".to_string() }
-//     } else {
-//         "idle".to_string()
-//     }
-// }
-
-// Update player state based on input
-pub fn update_input_state(player: &mut PlayerData, input: InputState, client_rot: Vector3, client_animation: String) {
-    // Calculate movement & animation based on RECEIVED input
-    let delta_time_estimate: f32 = 1.0 / 60.0; // Estimate client frame delta
-    let new_position = calculate_new_position(
-        &player.position,
-        &client_rot, // Use client rotation for direction calc
-        &input,
-        delta_time_estimate
-    );
+        spacetimedb::log::debug!(
+            "Client position of {} is {:.2} units from the server's",
+            player.identity,
+            drift
+        );
+    }
 
     // Update player state
-    player.position = new_position;
     player.rotation = client_rot;
-    player.current_animation = client_animation;
+    apply_input(player, input, now);
+    steps as f32 * fixed_step
+}
+
+// Stores the input and derives movement flags and the animation state from it
+pub fn apply_input(player: &mut PlayerData, input: InputState, now: Timestamp) {
+    // Jumps trigger on the press, not while the key is held
+    if input.jump && !player.input.jump {
+        player.jump_requested = true;
+    }
     player.input = input.clone(); // Store the input that caused this state
     player.last_input_seq = input.sequence;
     player.is_moving = input.forward || input.backward || input.left || input.right;
     player.is_running = player.is_moving && input.sprint;
-    player.is_attacking = input.attack;
-    player.is_casting = input.cast_spell;
+
+    // Attack and Cast are started by combat.rs and spells.rs once they actually happen
+    animation::update_animation(player, now);
 }
 
 // Update players logic (called from game_tick)
-pub fn update_players_logic(_ctx: &ReducerContext, _delta_time: f64) {
-    // In the simplified starter pack, we don't need to do anything in the game tick
-    // for players as they're updated directly through the update_player_input reducer
-    // This function is a placeholder for future expansion
+// Advances every player's stored input in fixed steps of `delta_time` seconds from the
+// player's own last_update, so movement keeps going between input packets without
+// re-simulating time update_player_input already integrated. Also expires finished
+// animations and pushes overlapping players apart. Players found outside the world bounds
+// are first returned to a spawn point. Only players whose state changed are written back.
+pub fn update_players_logic(ctx: &ReducerContext, delta_time: f32) {
+    let mut players: Vec<PlayerData> = ctx.db.player().iter().collect();
+    let terrain = Terrain::new(ctx);
+    let collision = CollisionWorld::new(ctx);
+    let world_bounds = bounds::world_bounds(ctx);
+    let mut changed = vec![false; players.len()];
+
+    for (index, player) in players.iter_mut().enumerate() {
+        let recovered = !world_bounds.contains(&player.position);
+        if recovered {
+            bounds::recover_player(ctx, player, "game_tick");
+        }
+
+        // Airborne players keep falling and pending jump presses fire without new input
+        let needs_simulation = player.is_moving || !player.is_grounded || player.jump_requested;
+        let steps = if needs_simulation { take_fixed_steps(player, ctx.timestamp, MAX_CATCH_UP_STEPS) } else { 0 };
+        let is_simulated = steps > 0;
+        if is_simulated {
+            let speed_multiplier = classes::class_stats(ctx, &player.character_class).speed_multiplier;
+            let (rotation, input) = (player.rotation.clone(), player.input.clone());
+            let hint_offset = take_hint_offset(player);
+            for _ in 0..steps {
+                simulate_step(&terrain, &collision, player, &rotation, &input, speed_multiplier, delta_time, ctx.timestamp);
+            }
+            apply_hint_offset(player, hint_offset);
+        }
+
+        let animation_changed = animation::update_animation(player, ctx.timestamp);
+        changed[index] = recovered || is_simulated || animation_changed;
+    }
+
+    for index in collision::separate_players(&mut players, &collision, &terrain) {
+        changed[index] = true;
+    }
+
+    for (player, changed) in players.into_iter().zip(changed) {
+        if changed {
+            ctx.db.player().identity().update(player);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    fn input(forward: bool, backward: bool, left: bool, right: bool, sprint: bool) -> InputState {
+        InputState { forward, backward, left, right, sprint, jump: false, attack: false, cast_spell: false, sequence: 0 }
+    }
+
+    fn yaw(y: f32) -> Vector3 {
+        Vector3 { x: 0.0, y, z: 0.0 }
+    }
+
+    #[test]
+    fn velocity_is_zero_without_movement_input() {
+        let velocity = calculate_velocity(&yaw(1.0), &input(false, false, false, false, true), 1.0);
+        assert_eq!(velocity, Vector3 { x: 0.0, y: 0.0, z: 0.0 });
+    }
+
+    #[test]
+    fn forward_moves_in_the_facing_direction() {
+        for rotation in [yaw(0.0), yaw(1.2), yaw(-2.5)] {
+            let velocity = calculate_velocity(&rotation, &input(true, false, false, false, false), 1.0);
+            let facing = facing_direction(&rotation);
+            assert!(approx(velocity.x, facing.x * PLAYER_SPEED));
+            assert!(approx(velocity.z, facing.z * PLAYER_SPEED));
+            assert_eq!(velocity.y, 0.0);
+        }
+    }
+
+    #[test]
+    fn diagonal_movement_is_not_faster() {
+        let velocity = calculate_velocity(&yaw(0.7), &input(true, false, true, false, false), 1.0);
+        let speed = (velocity.x * velocity.x + velocity.z * velocity.z).sqrt();
+        assert!(approx(speed, PLAYER_SPEED));
+    }
+
+    #[test]
+    fn opposite_inputs_cancel_out() {
+        let velocity = calculate_velocity(&yaw(0.3), &input(true, true, false, false, false), 1.0);
+        assert!(approx(velocity.x, 0.0) && approx(velocity.z, 0.0));
+    }
+
+    #[test]
+    fn sprint_and_class_multiplier_scale_speed() {
+        let velocity = calculate_velocity(&yaw(0.0), &input(false, false, false, true, true), 1.5);
+        let speed = (velocity.x * velocity.x + velocity.z * velocity.z).sqrt();
+        assert!(approx(speed, PLAYER_SPEED * 1.5 * SPRINT_MULTIPLIER));
+    }
+
+    #[test]
+    fn newer_sequences_are_accepted() {
+        assert!(is_newer_sequence(6, 5));
+        assert!(is_newer_sequence(1_000, 5));
+        assert!(!is_newer_sequence(5, 5));
+        assert!(!is_newer_sequence(4, 5));
+    }
+
+    #[test]
+    fn sequences_wrap_around() {
+        assert!(is_newer_sequence(0, u32::MAX));
+        assert!(is_newer_sequence(3, u32::MAX - 3));
+        assert!(!is_newer_sequence(u32::MAX, 0));
+    }
+
+    #[test]
+    fn sequences_half_the_range_ahead_are_stale() {
+        assert!(is_newer_sequence(5 + (1 << 31) - 1, 5));
+        assert!(!is_newer_sequence(5 + (1 << 31), 5));
+    }
+
+    const STEP_MICROS: i64 = 1_000_000 / SIMULATION_TICK_RATE_HZ as i64;
+
+    #[test]
+    fn elapsed_time_splits_into_whole_steps() {
+        assert_eq!(split_fixed_steps(0, 4), (0, 0));
+        assert_eq!(split_fixed_steps(STEP_MICROS - 1, 4), (0, 0));
+        assert_eq!(split_fixed_steps(STEP_MICROS, 4), (1, STEP_MICROS));
+        assert_eq!(split_fixed_steps(3 * STEP_MICROS + 10, 4), (3, 3 * STEP_MICROS));
+        assert_eq!(split_fixed_steps(-500, 4), (0, 0));
+    }
+
+    #[test]
+    fn time_beyond_max_steps_is_dropped() {
+        assert_eq!(split_fixed_steps(1_000_000, 4), (4, 1_000_000));
+    }
+
+    #[test]
+    fn leftover_time_carries_over() {
+        let mut player = crate::test_player();
+        let at = |micros: i64| Timestamp::from_micros_since_unix_epoch(micros);
+
+        assert_eq!(take_fixed_steps(&mut player, at(STEP_MICROS + STEP_MICROS / 2), 4), 1);
+        assert_eq!(player.last_update, at(STEP_MICROS));
+        // The half step left over completes with the next call
+        assert_eq!(take_fixed_steps(&mut player, at(2 * STEP_MICROS), 4), 1);
+        assert_eq!(player.last_update, at(2 * STEP_MICROS));
+        assert_eq!(take_fixed_steps(&mut player, at(2 * STEP_MICROS + 1), 4), 0);
+        assert_eq!(player.last_update, at(2 * STEP_MICROS));
+    }
+
+    const DT: f32 = 1.0 / SIMULATION_TICK_RATE_HZ as f32;
+
+    #[test]
+    fn jumping_from_the_ground_arcs_and_lands() {
+        let mut player = crate::test_player();
+        let now = Timestamp::UNIX_EPOCH;
+        step_vertical(&mut player, 0.0, true, DT, now);
+        assert!(!player.is_grounded);
+        assert!(player.position.y > 0.0);
+        assert_eq!(player.animation_state, AnimationState::Jump);
+
+        let mut peak: f32 = 0.0;
+        let mut steps = 0;
+        while !player.is_grounded && steps < 1_000 {
+            step_vertical(&mut player, 0.0, false, DT, now);
+            peak = peak.max(player.position.y);
+            steps += 1;
+        }
+        assert!(player.is_grounded);
+        assert_eq!(player.position.y, 0.0);
+        assert_eq!(player.vertical_velocity, 0.0);
+        let expected_peak = JUMP_VELOCITY * JUMP_VELOCITY / (2.0 * GRAVITY);
+        assert!((peak - expected_peak).abs() < 0.2, "peak {}", peak);
+    }
+
+    #[test]
+    fn no_second_jump_in_mid_air() {
+        let mut player = crate::test_player();
+        let now = Timestamp::UNIX_EPOCH;
+        step_vertical(&mut player, 0.0, true, DT, now);
+        for _ in 0..10 {
+            step_vertical(&mut player, 0.0, false, DT, now);
+        }
+        let velocity = player.vertical_velocity;
+        step_vertical(&mut player, 0.0, true, DT, now);
+        assert!(player.vertical_velocity < velocity);
+    }
+
+    #[test]
+    fn coyote_time_allows_a_late_jump() {
+        let mut player = crate::test_player();
+        let now = Timestamp::UNIX_EPOCH;
+        // Walked off a ledge higher than the snap distance
+        player.position.y = GROUND_SNAP_DISTANCE + 2.0;
+        step_vertical(&mut player, 0.0, false, DT, now);
+        assert!(!player.is_grounded);
+        assert!(player.airborne_seconds < COYOTE_TIME_SECONDS);
+        step_vertical(&mut player, 0.0, true, DT, now);
+        assert!(player.vertical_velocity > 0.0);
+    }
+
+    #[test]
+    fn dead_players_cannot_jump() {
+        let mut player = crate::test_player();
+        player.is_dead = true;
+        step_vertical(&mut player, 0.0, true, DT, Timestamp::UNIX_EPOCH);
+        assert!(player.is_grounded);
+        assert_eq!(player.position.y, 0.0);
+    }
+
+    #[test]
+    fn grounded_players_follow_gentle_drops() {
+        let mut player = crate::test_player();
+        player.position.y = 1.0;
+        step_vertical(&mut player, 1.0 - GROUND_SNAP_DISTANCE * 0.5, false, DT, Timestamp::UNIX_EPOCH);
+        assert!(player.is_grounded);
+        assert_eq!(player.position.y, 1.0 - GROUND_SNAP_DISTANCE * 0.5);
+    }
+
+    #[test]
+    fn falling_is_limited_to_terminal_velocity() {
+        let mut player = crate::test_player();
+        player.is_grounded = false;
+        player.position.y = 10_000.0;
+        for _ in 0..600 {
+            step_vertical(&mut player, 0.0, false, DT, Timestamp::UNIX_EPOCH);
+        }
+        assert_eq!(player.vertical_velocity, -TERMINAL_VELOCITY);
+    }
+
+    #[test]
+    fn jumps_trigger_on_the_press_only() {
+        let mut player = crate::test_player();
+        let now = Timestamp::UNIX_EPOCH;
+        let mut held = player.input.clone();
+        held.jump = true;
+
+        apply_input(&mut player, held.clone(), now);
+        assert!(player.jump_requested);
+        player.jump_requested = false;
+        // Still held: no new request
+        apply_input(&mut player, held.clone(), now);
+        assert!(!player.jump_requested);
+
+        let mut released = held.clone();
+        released.jump = false;
+        apply_input(&mut player, released, now);
+        apply_input(&mut player, held, now);
+        assert!(player.jump_requested);
+    }
+
+    #[test]
+    fn hints_are_removed_before_integrating() {
+        let mut player = crate::test_player();
+        let offset = Vector3 { x: 0.6, y: 0.0, z: -0.5 };
+        apply_hint_offset(&mut player, offset.clone());
+        assert_eq!(player.position, Vector3 { x: 0.6, y: 0.0, z: -0.5 });
+
+        // Taking the offset returns the integrated position, so repeated hints can't add up
+        assert_eq!(take_hint_offset(&mut player), offset);
+        assert_eq!(player.position, Vector3 { x: 0.0, y: 0.0, z: 0.0 });
+        assert_eq!(take_hint_offset(&mut player), Vector3 { x: 0.0, y: 0.0, z: 0.0 });
+    }
 }
@@ -0,0 +1,169 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - profiles.rs
+ *
+ * Account-level profile data, kept separate from characters and the in-world PlayerData row.
+ *
+ * Key components:
+ * - PlayerProfile: Public per-identity profile (username, color, update bookkeeping)
+ * - NameHistory: Every rename with old/new name and timestamp
+ * - ensure_profile: Creates the profile when an identity creates its first character
+ * - update_profile: Applies a rename and/or color change, rate limited per identity, and writes it
+ *   through to the active character
+ *
+ * When modifying:
+ * - The profile name is the account name; characters have their own names in character_roster,
+ *   which only change when a rename is requested
+ * - The profile color is the default for characters created without an explicit color
+ * - Renames and recolors also apply to the active character (the one in the world, otherwise the
+ *   most recently played) in character_roster, player and logged_out_player
+ * - PROFILE_UPDATE_COOLDOWN_SECONDS limits how often a profile can change
+ *
+ * Related files:
+ * - lib.rs: update_profile reducer
+ * - characters.rs: create_character creates profiles; the active character mirrors the profile
+ * - usernames.rs: Name validation shared with registration
+ * - palette.rs: Colors must come from the color_palette table
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+
+use crate::characters::{character_roster, Character};
+use crate::palette;
+use crate::usernames;
+use crate::{logged_out_player, player};
+
+// Minimum time between two profile updates for the same identity
+const PROFILE_UPDATE_COOLDOWN_SECONDS: u64 = 60;
+
+#[spacetimedb::table(name = player_profile, public)]
+#[derive(Clone)]
+pub struct PlayerProfile {
+    #[primary_key]
+    pub identity: Identity,
+    pub username: String,
+    pub color: String,
+    pub created_at: Timestamp,
+    pub last_profile_update: Timestamp,
+    pub rename_count: u32,
+}
+
+#[spacetimedb::table(name = name_history)]
+#[derive(Clone)]
+pub struct NameHistory {
+    #[primary_key]
+    #[auto_inc]
+    pub history_id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub old_name: String,
+    pub new_name: String,
+    pub changed_at: Timestamp,
+}
+
+// Returns the profile for `identity`, creating it if it doesn't exist yet
+pub fn ensure_profile(ctx: &ReducerContext, identity: Identity, username: &str, color: &str) -> PlayerProfile {
+    match ctx.db.player_profile().identity().find(identity) {
+        Some(profile) => profile,
+        None => ctx.db.player_profile().insert(PlayerProfile {
+            identity,
+            username: username.to_string(),
+            color: color.to_string(),
+            created_at: ctx.timestamp,
+            last_profile_update: Timestamp::UNIX_EPOCH,
+            rename_count: 0,
+        }),
+    }
+}
+
+// Applies a rename and/or color change for the caller
+pub fn update_profile(ctx: &ReducerContext, username: Option<String>, color: Option<String>) -> Result<(), String> {
+    let identity = ctx.sender;
+    let mut profile = ctx.db.player_profile().identity().find(identity)
+        .ok_or_else(|| "You need a character before you can edit your profile".to_string())?;
+
+    let since_last_update = ctx.timestamp
+        .duration_since(profile.last_profile_update)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    if since_last_update < PROFILE_UPDATE_COOLDOWN_SECONDS {
+        return Err(format!(
+            "You can update your profile again in {} seconds",
+            PROFILE_UPDATE_COOLDOWN_SECONDS - since_last_update
+        ));
+    }
+
+    let new_username = match username {
+        Some(name) => Some(usernames::validate_username(ctx, identity, &name)?),
+        None => None,
+    };
+    let new_color = match color {
+        Some(color) => Some(palette::resolve_color(ctx, &color)?),
+        None => None,
+    };
+    if new_username.is_none() && new_color.is_none() {
+        return Err("Nothing to update".to_string());
+    }
+    let active = active_character(ctx, identity);
+    if let (Some(name), Some(active)) = (&new_username, &active) {
+        let lowered = name.to_lowercase();
+        let duplicate = ctx.db.character_roster()
+            .owner()
+            .filter(&identity)
+            .any(|c| c.character_id != active.character_id && c.name.to_lowercase() == lowered);
+        if duplicate {
+            return Err(format!("You already have a character named '{}'", name));
+        }
+    }
+
+    if let Some(name) = new_username.clone().filter(|name| *name != profile.username) {
+        ctx.db.name_history().insert(NameHistory {
+            history_id: 0,
+            identity,
+            old_name: profile.username.clone(),
+            new_name: name.clone(),
+            changed_at: ctx.timestamp,
+        });
+        spacetimedb::log::info!("Player {} renamed from {} to {}", identity, profile.username, name);
+        profile.username = name;
+        profile.rename_count += 1;
+    }
+    if let Some(color) = &new_color {
+        profile.color = color.clone();
+    }
+    profile.last_profile_update = ctx.timestamp;
+
+    // Mirror what changed onto the active character's roster, in-world and saved rows; the name
+    // is only written by a rename (checked against the owner's other characters above)
+    if let Some(mut character) = active {
+        if let Some(name) = new_username {
+            character.name = name;
+        }
+        if let Some(color) = new_color {
+            character.color = color;
+        }
+        if let Some(mut player) = ctx.db.player().identity().find(identity) {
+            player.username = character.name.clone();
+            player.color = character.color.clone();
+            ctx.db.player().identity().update(player);
+        }
+        if let Some(mut logged_out) = ctx.db.logged_out_player().character_id().find(character.character_id) {
+            logged_out.username = character.name.clone();
+            logged_out.color = character.color.clone();
+            ctx.db.logged_out_player().character_id().update(logged_out);
+        }
+        ctx.db.character_roster().character_id().update(character);
+    }
+    ctx.db.player_profile().identity().update(profile);
+    Ok(())
+}
+
+// The character in the world for `identity`, otherwise the one played most recently
+fn active_character(ctx: &ReducerContext, identity: Identity) -> Option<Character> {
+    if let Some(player) = ctx.db.player().identity().find(identity) {
+        return ctx.db.character_roster().character_id().find(player.character_id);
+    }
+    ctx.db.character_roster()
+        .owner()
+        .filter(&identity)
+        .max_by_key(|c| c.last_played)
+}
@@ -0,0 +1,385 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - rate_limit.rs
+ *
+ * Per-identity token-bucket rate limiting for client-callable reducers.
+ *
+ * Key components:
+ * - RateLimitBudget: Configurable bucket capacity and refill rate per budget name
+ * - RateLimitBucket: Current tokens per (identity, budget)
+ * - RateLimitOffender: Dropped calls per identity within the escalation window
+ * - RateLimitWarning: Public notice written when a client is warned, so it can back off
+ * - allow: Consumes a token, or records a drop and escalates (drop, warn, kick)
+ * - run_limited: Wraps a reducer body: checks allow, and turns a failed body into a committed
+ *   RejectedCall row so the failure still costs its token
+ * - RejectedCall: Public notice of the caller's last rejected call, with the error message
+ * - RateLimitSweepSchedule: Scheduled table driving sweep_rate_limits, which deletes idle state
+ *   (including chat.rs's per-identity message counters)
+ *
+ * Budgets:
+ * - BUDGET_INPUT: update_player_input (clients send up to 60 inputs per second)
+ * - BUDGET_ACTION: Everything else a player triggers (casts, chat, roster, profile, reports)
+ *
+ * When modifying:
+ * - Rate-limited reducers return Ok(()) whether the call was dropped, rejected or applied; an
+ *   Err would roll back the spent token and the drop counters along with everything else
+ * - Because a rejected body's transaction is committed, bodies run through run_limited must
+ *   validate everything before they write
+ * - Budgets can be changed at runtime with admin_set_rate_limit
+ * - The sweep only deletes rows that no longer affect a decision: buckets that have refilled
+ *   completely (a new bucket starts full) and offender/warning/rejection rows whose window has passed, so
+ *   reconnecting doesn't reset anything
+ *
+ * Related files:
+ * - lib.rs: Rate-limited reducers run through run_limited (update_player_input calls allow),
+ *   calls init_rate_limits
+ * - client/src/App.tsx: Shows RejectedCall messages for roster actions
+ * - metrics.rs: Dropped calls are counted in the metrics windows
+ */
+
+use spacetimedb::{Identity, ReducerContext, ScheduleAt, Table, Timestamp};
+use std::time::Duration;
+
+use crate::admin::{self, Role};
+use crate::chat;
+use crate::metrics;
+use crate::player;
+
+pub const BUDGET_INPUT: &str = "input";
+pub const BUDGET_ACTION: &str = "action";
+
+// Escalation: drops within ESCALATION_WINDOW_SECONDS that trigger a warning and a kick
+const ESCALATION_WINDOW_SECONDS: u64 = 10;
+const WARN_AFTER_DROPS: u32 = 30;
+const KICK_AFTER_DROPS: u32 = 300;
+const SWEEP_INTERVAL_SECONDS: u64 = 60;
+
+#[spacetimedb::table(name = rate_limit_budget)]
+#[derive(Clone)]
+pub struct RateLimitBudget {
+    #[primary_key]
+    pub name: String,
+    pub capacity: f32,
+    pub refill_per_second: f32,
+}
+
+#[spacetimedb::table(name = rate_limit_bucket)]
+#[derive(Clone)]
+pub struct RateLimitBucket {
+    #[primary_key]
+    #[auto_inc]
+    pub bucket_id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub budget: String,
+    pub tokens: f32,
+    pub last_refill: Timestamp,
+}
+
+#[spacetimedb::table(name = rate_limit_offender)]
+#[derive(Clone)]
+pub struct RateLimitOffender {
+    #[primary_key]
+    pub identity: Identity,
+    pub window_start: Timestamp,
+    pub dropped_in_window: u32,
+    pub total_dropped: u64,
+    pub warned: bool,
+}
+
+#[spacetimedb::table(name = rate_limit_warning, public)]
+#[derive(Clone)]
+pub struct RateLimitWarning {
+    #[primary_key]
+    pub identity: Identity,
+    pub budget: String,
+    pub warned_at: Timestamp,
+}
+
+#[spacetimedb::table(name = rejected_call, public)]
+#[derive(Clone)]
+pub struct RejectedCall {
+    #[primary_key]
+    pub identity: Identity,
+    pub reducer: String,
+    pub message: String,
+    pub rejected_at: Timestamp,
+}
+
+#[spacetimedb::table(name = rate_limit_sweep_schedule, scheduled(sweep_rate_limits))]
+pub struct RateLimitSweepSchedule {
+    #[primary_key]
+    #[auto_inc]
+    scheduled_id: u64,
+    scheduled_at: ScheduleAt,
+}
+
+// Called from init: seeds the default budgets and schedules the sweep of idle state
+pub fn init_rate_limits(ctx: &ReducerContext) {
+    if ctx.db.rate_limit_sweep_schedule().count() == 0 {
+        spacetimedb::log::info!("[INIT] Scheduling rate limit sweep (every {} seconds)...", SWEEP_INTERVAL_SECONDS);
+        ctx.db.rate_limit_sweep_schedule().insert(RateLimitSweepSchedule {
+            scheduled_id: 0,
+            scheduled_at: ScheduleAt::Interval(Duration::from_secs(SWEEP_INTERVAL_SECONDS).into()),
+        });
+    }
+
+    let defaults = [
+        // 60 inputs per second sustained, with a one second burst allowance
+        (BUDGET_INPUT, 120.0, 60.0),
+        (BUDGET_ACTION, 10.0, 2.0),
+    ];
+    for (name, capacity, refill_per_second) in defaults {
+        if ctx.db.rate_limit_budget().name().find(&name.to_string()).is_none() {
+            ctx.db.rate_limit_budget().insert(RateLimitBudget {
+                name: name.to_string(),
+                capacity,
+                refill_per_second,
+            });
+        }
+    }
+}
+
+// Tokens in a bucket after refilling for `elapsed_seconds`, capped at the budget's capacity
+fn refilled_tokens(tokens: f32, elapsed_seconds: f32, config: &RateLimitBudget) -> f32 {
+    (tokens + elapsed_seconds * config.refill_per_second).min(config.capacity)
+}
+
+// Consumes a token from the caller's `budget` bucket. Returns false if the call should be
+// dropped, after recording the drop and applying escalation.
+pub fn allow(ctx: &ReducerContext, budget: &str) -> bool {
+    // Scheduled reducers run as the module itself and are never limited
+    if ctx.sender == ctx.identity() {
+        return true;
+    }
+    let Some(config) = ctx.db.rate_limit_budget().name().find(&budget.to_string()) else {
+        return true;
+    };
+
+    let identity = ctx.sender;
+    let mut bucket = match ctx.db.rate_limit_bucket().identity().filter(&identity).find(|b| b.budget == budget) {
+        Some(bucket) => bucket,
+        None => ctx.db.rate_limit_bucket().insert(RateLimitBucket {
+            bucket_id: 0,
+            identity,
+            budget: budget.to_string(),
+            tokens: config.capacity,
+            last_refill: ctx.timestamp,
+        }),
+    };
+
+    let elapsed = ctx.timestamp
+        .duration_since(bucket.last_refill)
+        .map(|d| d.as_secs_f32())
+        .unwrap_or(0.0);
+    bucket.tokens = refilled_tokens(bucket.tokens, elapsed, &config);
+    bucket.last_refill = ctx.timestamp;
+
+    let allowed = bucket.tokens >= 1.0;
+    if allowed {
+        bucket.tokens -= 1.0;
+    }
+    ctx.db.rate_limit_bucket().bucket_id().update(bucket);
+
+    if !allowed {
+        record_drop(ctx, identity, budget);
+    }
+    allowed
+}
+
+// Runs the body of the rate-limited reducer `reducer`. A failed body is not returned as an
+// Err, which would roll back the token it spent; its message is stored in RejectedCall for
+// the caller instead. `body` must not write anything before it can fail.
+pub fn run_limited(
+    ctx: &ReducerContext,
+    budget: &str,
+    reducer: &str,
+    body: impl FnOnce() -> Result<(), String>,
+) -> Result<(), String> {
+    if !allow(ctx, budget) {
+        return Ok(());
+    }
+    if let Err(message) = body() {
+        spacetimedb::log::debug!("{} by {} rejected: {}", reducer, ctx.sender, message);
+        let rejection = RejectedCall {
+            identity: ctx.sender,
+            reducer: reducer.to_string(),
+            message,
+            rejected_at: ctx.timestamp,
+        };
+        if ctx.db.rejected_call().identity().find(ctx.sender).is_some() {
+            ctx.db.rejected_call().identity().update(rejection);
+        } else {
+            ctx.db.rejected_call().insert(rejection);
+        }
+    }
+    Ok(())
+}
+
+fn record_drop(ctx: &ReducerContext, identity: Identity, budget: &str) {
+    metrics::record_dropped_call(ctx);
+
+    let mut offender = ctx.db.rate_limit_offender().identity().find(identity).unwrap_or(RateLimitOffender {
+        identity,
+        window_start: ctx.timestamp,
+        dropped_in_window: 0,
+        total_dropped: 0,
+        warned: false,
+    });
+    let window_elapsed = ctx.timestamp
+        .duration_since(offender.window_start)
+        .map(|d| d.as_secs() >= ESCALATION_WINDOW_SECONDS)
+        .unwrap_or(false);
+    if window_elapsed {
+        offender.window_start = ctx.timestamp;
+        offender.dropped_in_window = 0;
+        offender.warned = false;
+    }
+    offender.dropped_in_window += 1;
+    offender.total_dropped += 1;
+
+    if offender.dropped_in_window >= KICK_AFTER_DROPS {
+        if let Some(player) = ctx.db.player().identity().find(identity) {
+            spacetimedb::log::warn!("Kicking {} for exceeding the '{}' rate limit.", identity, budget);
+            crate::move_to_logged_out(ctx, player, ctx.timestamp);
+        }
+        offender.window_start = ctx.timestamp;
+        offender.dropped_in_window = 0;
+        offender.warned = false;
+    } else if offender.dropped_in_window >= WARN_AFTER_DROPS && !offender.warned {
+        spacetimedb::log::warn!("Rate limit warning for {} on '{}'.", identity, budget);
+        offender.warned = true;
+        let warning = RateLimitWarning { identity, budget: budget.to_string(), warned_at: ctx.timestamp };
+        if ctx.db.rate_limit_warning().identity().find(identity).is_some() {
+            ctx.db.rate_limit_warning().identity().update(warning);
+        } else {
+            ctx.db.rate_limit_warning().insert(warning);
+        }
+    }
+
+    if ctx.db.rate_limit_offender().identity().find(identity).is_some() {
+        ctx.db.rate_limit_offender().identity().update(offender);
+    } else {
+        ctx.db.rate_limit_offender().insert(offender);
+    }
+}
+
+// Deletes buckets that have refilled to capacity and offender/warning/rejection rows whose
+// escalation window has passed; recreating any of them later gives the same result
+#[spacetimedb::reducer]
+pub fn sweep_rate_limits(ctx: &ReducerContext, _schedule: RateLimitSweepSchedule) -> Result<(), String> {
+    // Only the scheduler may sweep
+    if ctx.sender != ctx.identity() {
+        return Err("sweep_rate_limits may only be invoked by the scheduler".to_string());
+    }
+
+    let seconds_since = |timestamp: Timestamp| {
+        ctx.timestamp
+            .duration_since(timestamp)
+            .map(|d| d.as_secs_f32())
+            .unwrap_or(0.0)
+    };
+    let idle_buckets: Vec<RateLimitBucket> = ctx.db.rate_limit_bucket()
+        .iter()
+        .filter(|bucket| match ctx.db.rate_limit_budget().name().find(&bucket.budget) {
+            Some(config) => refilled_tokens(bucket.tokens, seconds_since(bucket.last_refill), &config) >= config.capacity,
+            None => true,
+        })
+        .collect();
+    let window = ESCALATION_WINDOW_SECONDS as f32;
+    let stale_offenders: Vec<RateLimitOffender> = ctx.db.rate_limit_offender()
+        .iter()
+        .filter(|offender| seconds_since(offender.window_start) >= window)
+        .collect();
+    let stale_warnings: Vec<RateLimitWarning> = ctx.db.rate_limit_warning()
+        .iter()
+        .filter(|warning| seconds_since(warning.warned_at) >= window)
+        .collect();
+    let stale_rejections: Vec<RejectedCall> = ctx.db.rejected_call()
+        .iter()
+        .filter(|rejection| seconds_since(rejection.rejected_at) >= window)
+        .collect();
+
+    let mut removed = idle_buckets.len() + stale_offenders.len() + stale_warnings.len() + stale_rejections.len();
+    for bucket in idle_buckets {
+        ctx.db.rate_limit_bucket().delete(bucket);
+    }
+    for offender in stale_offenders {
+        ctx.db.rate_limit_offender().delete(offender);
+    }
+    for warning in stale_warnings {
+        ctx.db.rate_limit_warning().delete(warning);
+    }
+    for rejection in stale_rejections {
+        ctx.db.rejected_call().delete(rejection);
+    }
+    removed += chat::sweep_rate_limits(ctx);
+    if removed > 0 {
+        spacetimedb::log::debug!("Rate limit sweep removed {} idle rows.", removed);
+    }
+    Ok(())
+}
+
+pub fn set_budget(ctx: &ReducerContext, name: String, capacity: f32, refill_per_second: f32) -> Result<(), String> {
+    admin::require_role(ctx, Role::Admin)?;
+    if !(capacity >= 1.0 && refill_per_second > 0.0 && capacity.is_finite() && refill_per_second.is_finite()) {
+        return Err("Capacity must be at least 1 and the refill rate positive".to_string());
+    }
+    let budget = RateLimitBudget { name: name.clone(), capacity, refill_per_second };
+    if ctx.db.rate_limit_budget().name().find(&name).is_some() {
+        ctx.db.rate_limit_budget().name().update(budget);
+    } else {
+        ctx.db.rate_limit_budget().insert(budget);
+    }
+    admin::audit(ctx, "set_rate_limit", None, format!("{}: capacity {}, {}/s", name, capacity, refill_per_second));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget(capacity: f32, refill_per_second: f32) -> RateLimitBudget {
+        RateLimitBudget { name: "test".to_string(), capacity, refill_per_second }
+    }
+
+    #[test]
+    fn buckets_refill_at_the_budget_rate() {
+        let config = budget(10.0, 2.0);
+        assert_eq!(refilled_tokens(3.0, 1.5, &config), 6.0);
+        assert_eq!(refilled_tokens(3.0, 0.0, &config), 3.0);
+    }
+
+    #[test]
+    fn buckets_never_exceed_capacity() {
+        let config = budget(10.0, 2.0);
+        assert_eq!(refilled_tokens(9.0, 60.0, &config), 10.0);
+    }
+
+    #[test]
+    fn sustained_rate_is_the_refill_rate_after_the_burst() {
+        // 60 calls per second against the default input budget, for five seconds
+        let config = budget(120.0, 60.0);
+        let mut tokens = config.capacity;
+        let mut allowed = 0;
+        for _ in 0..300 {
+            tokens = refilled_tokens(tokens, 1.0 / 60.0, &config);
+            if tokens >= 1.0 {
+                tokens -= 1.0;
+                allowed += 1;
+            }
+        }
+        assert_eq!(allowed, 300);
+
+        // Twice that rate drains the burst allowance and is then held to the refill rate
+        let mut tokens = config.capacity;
+        let mut allowed = 0;
+        for _ in 0..600 {
+            tokens = refilled_tokens(tokens, 1.0 / 120.0, &config);
+            if tokens >= 1.0 {
+                tokens -= 1.0;
+                allowed += 1;
+            }
+        }
+        assert!((418..=421).contains(&allowed), "allowed {}", allowed);
+    }
+}
@@ -0,0 +1,218 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - retention.rs
+ *
+ * Player data deletion and retention of saved characters.
+ *
+ * Key components:
+ * - RetentionConfig: Singleton with the retention period and whether purged characters are archived
+ * - ArchivedCharacter: Saved state of characters removed by the cleanup job (only when archiving)
+ * - RetentionSchedule: Scheduled table driving purge_stale_characters every hour
+ * - delete_account: Removes everything stored for the calling identity
+ *
+ * Flow:
+ * - init_retention is called from the module's init reducer
+ * - purge_stale_characters removes character_roster entries whose last_seen is older than
+ *   retention_days, together with their logged_out_player row if there is one; characters
+ *   currently in the world are never purged
+ *
+ * When modifying:
+ * - retention_days = 0 disables the cleanup; edit retention_config with `spacetime sql`
+ * - Movement violations are moderation records and are kept when an account is deleted
+ * - Rate limit state (rate_limit_* and chat_rate_limit rows) is kept too, so deleting an account
+ *   doesn't reset escalation; rate_limit::sweep_rate_limits expires it
+ *
+ * Related files:
+ * - lib.rs: delete_account reducer, calls init_retention
+ * - characters.rs: Character roster and saved state lifecycle
+ */
+
+use spacetimedb::{Identity, ReducerContext, ScheduleAt, Table, Timestamp};
+use std::time::Duration;
+
+use crate::characters::{character_roster, Character};
+use crate::classes;
+use crate::common::Vector3;
+use crate::profiles::{name_history, player_profile};
+use crate::spells::{active_cast, spell_cooldown};
+use crate::{input_ack, logged_out_player, player, LoggedOutPlayerData};
+
+const DEFAULT_RETENTION_DAYS: u32 = 30;
+const CLEANUP_INTERVAL_SECONDS: u64 = 3600;
+const MICROS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000;
+
+#[spacetimedb::table(name = retention_config)]
+#[derive(Clone)]
+pub struct RetentionConfig {
+    #[primary_key]
+    pub id: u32,
+    pub retention_days: u32,
+    // When true, purged characters are copied to archived_character instead of being dropped
+    pub archive: bool,
+}
+
+#[spacetimedb::table(name = archived_character)]
+#[derive(Clone)]
+pub struct ArchivedCharacter {
+    #[primary_key]
+    pub character_id: u64,
+    #[index(btree)]
+    pub owner: Identity,
+    pub name: String,
+    pub character_class: String,
+    pub color: String,
+    pub position: Vector3,
+    pub rotation: Vector3,
+    pub health: i32,
+    pub max_health: i32,
+    pub mana: i32,
+    pub max_mana: i32,
+    pub last_seen: Timestamp,
+    pub archived_at: Timestamp,
+}
+
+#[spacetimedb::table(name = retention_schedule, scheduled(purge_stale_characters))]
+pub struct RetentionSchedule {
+    #[primary_key]
+    #[auto_inc]
+    scheduled_id: u64,
+    scheduled_at: ScheduleAt,
+}
+
+// Called from init: creates the default config and schedules the cleanup job
+pub fn init_retention(ctx: &ReducerContext) {
+    if ctx.db.retention_config().id().find(0).is_none() {
+        ctx.db.retention_config().insert(RetentionConfig {
+            id: 0,
+            retention_days: DEFAULT_RETENTION_DAYS,
+            archive: false,
+        });
+    }
+
+    if ctx.db.retention_schedule().count() == 0 {
+        spacetimedb::log::info!("[INIT] Scheduling character retention cleanup (every {} seconds)...", CLEANUP_INTERVAL_SECONDS);
+        ctx.db.retention_schedule().insert(RetentionSchedule {
+            scheduled_id: 0,
+            scheduled_at: ScheduleAt::Interval(Duration::from_secs(CLEANUP_INTERVAL_SECONDS).into()),
+        });
+    }
+}
+
+#[spacetimedb::reducer]
+pub fn purge_stale_characters(ctx: &ReducerContext, _schedule: RetentionSchedule) -> Result<(), String> {
+    // Only the scheduler may run the cleanup
+    if ctx.sender != ctx.identity() {
+        return Err("purge_stale_characters may only be invoked by the scheduler".to_string());
+    }
+
+    let Some(config) = ctx.db.retention_config().id().find(0) else {
+        return Ok(());
+    };
+    if config.retention_days == 0 {
+        return Ok(());
+    }
+
+    let cutoff = Timestamp::from_micros_since_unix_epoch(
+        ctx.timestamp.to_micros_since_unix_epoch() - config.retention_days as i64 * MICROS_PER_DAY,
+    );
+    let stale: Vec<Character> = ctx.db.character_roster()
+        .iter()
+        .filter(|c| c.last_seen < cutoff)
+        .filter(|c| !ctx.db.player().identity().find(c.owner).is_some_and(|p| p.character_id == c.character_id))
+        .collect();
+
+    for character in &stale {
+        let saved = ctx.db.logged_out_player().character_id().find(character.character_id);
+        if config.archive {
+            ctx.db.archived_character().character_id().delete(character.character_id);
+            ctx.db.archived_character().insert(archive_entry(ctx, character, saved.as_ref()));
+        }
+        ctx.db.logged_out_player().character_id().delete(character.character_id);
+        ctx.db.character_roster().character_id().delete(character.character_id);
+    }
+    // Saved state left behind by a roster entry that no longer exists
+    let orphaned: Vec<LoggedOutPlayerData> = ctx.db.logged_out_player()
+        .iter()
+        .filter(|p| p.last_seen < cutoff && ctx.db.character_roster().character_id().find(p.character_id).is_none())
+        .collect();
+    for saved in orphaned {
+        ctx.db.logged_out_player().delete(saved);
+    }
+
+    if !stale.is_empty() {
+        spacetimedb::log::info!(
+            "Retention cleanup {} {} characters not seen for {} days.",
+            if config.archive { "archived" } else { "purged" },
+            stale.len(),
+            config.retention_days
+        );
+    }
+    Ok(())
+}
+
+// Archived copy of a purged character. Characters that never entered the world have no
+// saved state and are archived with their class base stats at the origin.
+fn archive_entry(ctx: &ReducerContext, character: &Character, saved: Option<&LoggedOutPlayerData>) -> ArchivedCharacter {
+    let (position, rotation, health, max_health, mana, max_mana) = match saved {
+        Some(saved) => (saved.position.clone(), saved.rotation.clone(), saved.health, saved.max_health, saved.mana, saved.max_mana),
+        None => {
+            let class = classes::class_stats(ctx, &character.character_class);
+            let origin = Vector3 { x: 0.0, y: 0.0, z: 0.0 };
+            (origin.clone(), origin, class.base_health, class.base_health, class.base_mana, class.base_mana)
+        }
+    };
+    ArchivedCharacter {
+        character_id: character.character_id,
+        owner: character.owner,
+        name: character.name.clone(),
+        character_class: character.character_class.clone(),
+        color: character.color.clone(),
+        position,
+        rotation,
+        health,
+        max_health,
+        mana,
+        max_mana,
+        last_seen: character.last_seen,
+        archived_at: ctx.timestamp,
+    }
+}
+
+// Removes every character, profile and piece of game state belonging to the caller
+pub fn delete_account(ctx: &ReducerContext) -> Result<(), String> {
+    let identity = ctx.sender;
+    if ctx.db.player_profile().identity().find(identity).is_none()
+        && ctx.db.character_roster().owner().filter(&identity).next().is_none()
+    {
+        return Err("There is no account data to delete".to_string());
+    }
+
+    // Leave the world without saving the active character
+    ctx.db.player().identity().delete(identity);
+    ctx.db.input_ack().identity().delete(identity);
+    ctx.db.active_cast().caster().delete(identity);
+
+    let saved: Vec<LoggedOutPlayerData> = ctx.db.logged_out_player().identity().filter(&identity).collect();
+    for row in saved {
+        ctx.db.logged_out_player().delete(row);
+    }
+    let characters: Vec<_> = ctx.db.character_roster().owner().filter(&identity).collect();
+    for character in characters {
+        ctx.db.character_roster().delete(character);
+    }
+    let archived: Vec<ArchivedCharacter> = ctx.db.archived_character().owner().filter(&identity).collect();
+    for character in archived {
+        ctx.db.archived_character().delete(character);
+    }
+    let cooldowns: Vec<_> = ctx.db.spell_cooldown().caster().filter(&identity).collect();
+    for cooldown in cooldowns {
+        ctx.db.spell_cooldown().delete(cooldown);
+    }
+    let history: Vec<_> = ctx.db.name_history().identity().filter(&identity).collect();
+    for entry in history {
+        ctx.db.name_history().delete(entry);
+    }
+    ctx.db.player_profile().identity().delete(identity);
+
+    spacetimedb::log::info!("Deleted all account data for {}.", identity);
+    Ok(())
+}
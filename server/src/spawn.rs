@@ -0,0 +1,286 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - spawn.rs
+ *
+ * Spawn points, death and respawning.
+ *
+ * Key components:
+ * - SpawnPoint: Public table of spawn locations (optionally restricted to a team)
+ * - SpawnConfig: Singleton holding the selection strategy, round-robin cursor and respawn delay
+ * - select_spawn_point: Picks a spawn for registration and respawn using the configured strategy
+ *   (positions are placed on the terrain surface)
+ * - restore_position: Restores a rejoining player's saved location, with validation and
+ *   an optional return-to-town policy after long absences
+ * - nearest_spawn_point: Recovery destination for players found outside the world bounds
+ * - kill_player: Puts a player into the dead state and schedules the respawn
+ * - process_respawns: Respawns players whose timer elapsed (called from game_tick)
+ *
+ * Strategies:
+ * - RoundRobin: Cycles through spawn points in spawn_id order
+ * - FarthestFromEnemies: Maximises distance to the nearest living enemy
+ * - TeamBased: Like FarthestFromEnemies, restricted to the player's team spawns
+ *
+ * Related files:
+ * - lib.rs: Calls process_respawns from game_tick
+ * - characters.rs: Uses select_spawn_point and restore_position when entering the world
+ * - combat.rs: Calls kill_player when health reaches zero
+ * - terrain.rs: Ground height for spawn and restore positions
+ * - bounds.rs: Saved positions outside the world bounds aren't restored; recovery uses
+ *   nearest_spawn_point
+ * - collision.rs: Saved positions inside obstacles aren't restored
+ */
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::animation;
+use crate::bounds;
+use crate::collision::CollisionWorld;
+use crate::common::{AnimationState, Vector3};
+use crate::player_logic;
+use crate::spells;
+use crate::terrain::Terrain;
+use crate::{player, LoggedOutPlayerData, PlayerData};
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum SpawnStrategy {
+    RoundRobin,
+    FarthestFromEnemies,
+    TeamBased,
+}
+
+#[spacetimedb::table(name = spawn_point, public)]
+#[derive(Clone)]
+pub struct SpawnPoint {
+    #[primary_key]
+    #[auto_inc]
+    pub spawn_id: u64,
+    pub position: Vector3,
+    pub yaw: f32,
+    // 0 means the spawn point can be used by any team
+    pub team: u32,
+}
+
+#[spacetimedb::table(name = spawn_config)]
+#[derive(Clone)]
+pub struct SpawnConfig {
+    #[primary_key]
+    pub id: u32,
+    pub strategy: SpawnStrategy,
+    pub next_index: u32,
+    pub respawn_delay_seconds: f32,
+    // Rejoining players offline longer than this return to a spawn point (0 disables)
+    pub return_to_town_after_hours: u32,
+}
+
+const DEFAULT_SPAWN_RING_RADIUS: f32 = 10.0;
+const DEFAULT_SPAWN_COUNT: u32 = 8;
+const DEFAULT_RESPAWN_DELAY_SECONDS: f32 = 5.0;
+const DEFAULT_RETURN_TO_TOWN_AFTER_HOURS: u32 = 24;
+const TEAM_COUNT: u32 = 2;
+
+// Called from init: seeds a ring of spawn points and the default config
+pub fn init_spawns(ctx: &ReducerContext) {
+    if ctx.db.spawn_point().count() == 0 {
+        for i in 0..DEFAULT_SPAWN_COUNT {
+            let angle = i as f32 / DEFAULT_SPAWN_COUNT as f32 * std::f32::consts::TAU;
+            ctx.db.spawn_point().insert(SpawnPoint {
+                spawn_id: 0,
+                position: Vector3 {
+                    x: angle.sin() * DEFAULT_SPAWN_RING_RADIUS,
+                    y: 1.0,
+                    z: angle.cos() * DEFAULT_SPAWN_RING_RADIUS,
+                },
+                // Face the centre of the ring
+                yaw: angle + std::f32::consts::PI,
+                // Alternate halves of the ring between the two teams
+                team: if i < DEFAULT_SPAWN_COUNT / 2 { 1 } else { 2 },
+            });
+        }
+    }
+
+    // Creates the default config if missing
+    config(ctx);
+}
+
+// The spawn config singleton, created with defaults on first use
+fn config(ctx: &ReducerContext) -> SpawnConfig {
+    match ctx.db.spawn_config().id().find(0) {
+        Some(config) => config,
+        None => ctx.db.spawn_config().insert(SpawnConfig {
+            id: 0,
+            strategy: SpawnStrategy::FarthestFromEnemies,
+            next_index: 0,
+            respawn_delay_seconds: DEFAULT_RESPAWN_DELAY_SECONDS,
+            return_to_town_after_hours: DEFAULT_RETURN_TO_TOWN_AFTER_HOURS,
+        }),
+    }
+}
+
+// Team for a newly entering player: the smallest team under TeamBased, otherwise 0
+pub fn assign_team(ctx: &ReducerContext) -> u32 {
+    if config(ctx).strategy != SpawnStrategy::TeamBased {
+        return 0;
+    }
+    (1..=TEAM_COUNT)
+        .min_by_key(|team| ctx.db.player().iter().filter(|p| p.team == *team).count())
+        .unwrap_or(1)
+}
+
+// Picks a spawn position and rotation for `identity` using the configured strategy
+pub fn select_spawn_point(ctx: &ReducerContext, identity: Identity, team: u32) -> (Vector3, Vector3) {
+    let mut config = config(ctx);
+    let mut points: Vec<SpawnPoint> = ctx.db.spawn_point().iter().collect();
+    points.sort_by_key(|p| p.spawn_id);
+
+    if points.is_empty() {
+        spacetimedb::log::warn!("No spawn points defined, spawning {} at the origin.", identity);
+        let origin = Terrain::new(ctx).snap_to_ground(&Vector3 { x: 0.0, y: 0.0, z: 0.0 });
+        return (origin, Vector3 { x: 0.0, y: 0.0, z: 0.0 });
+    }
+
+    let chosen = match config.strategy {
+        SpawnStrategy::RoundRobin => {
+            let index = config.next_index as usize % points.len();
+            config.next_index = ((index + 1) % points.len()) as u32;
+            ctx.db.spawn_config().id().update(config);
+            points[index].clone()
+        }
+        SpawnStrategy::FarthestFromEnemies => farthest_from_enemies(ctx, identity, team, &points),
+        SpawnStrategy::TeamBased => {
+            let team_points: Vec<SpawnPoint> = points
+                .iter()
+                .filter(|p| p.team == team || p.team == 0)
+                .cloned()
+                .collect();
+            if team_points.is_empty() {
+                farthest_from_enemies(ctx, identity, team, &points)
+            } else {
+                farthest_from_enemies(ctx, identity, team, &team_points)
+            }
+        }
+    };
+
+    (Terrain::new(ctx).snap_to_ground(&chosen.position), Vector3 { x: 0.0, y: chosen.yaw, z: 0.0 })
+}
+
+fn farthest_from_enemies(ctx: &ReducerContext, identity: Identity, team: u32, points: &[SpawnPoint]) -> SpawnPoint {
+    let enemies: Vec<Vector3> = ctx.db.player()
+        .iter()
+        .filter(|p| p.identity != identity && !p.is_dead && (team == 0 || p.team != team))
+        .map(|p| p.position)
+        .collect();
+
+    let nearest_enemy = |point: &SpawnPoint| -> f32 {
+        enemies
+            .iter()
+            .map(|e| {
+                let dx = e.x - point.position.x;
+                let dz = e.z - point.position.z;
+                dx * dx + dz * dz
+            })
+            .fold(f32::MAX, f32::min)
+    };
+
+    points
+        .iter()
+        .max_by(|a, b| nearest_enemy(a).total_cmp(&nearest_enemy(b)))
+        .cloned()
+        .unwrap_or_else(|| points[0].clone())
+}
+
+// Position and rotation for a rejoining player: the saved location if it is still valid,
+// otherwise (or after the return-to-town period) a freshly selected spawn point
+pub fn restore_position(ctx: &ReducerContext, saved: &LoggedOutPlayerData, team: u32) -> (Vector3, Vector3) {
+    let config = config(ctx);
+    let offline_hours = ctx.timestamp
+        .duration_since(saved.last_seen)
+        .map(|d| d.as_secs() / 3600)
+        .unwrap_or(0);
+
+    if config.return_to_town_after_hours > 0 && offline_hours >= config.return_to_town_after_hours as u64 {
+        spacetimedb::log::info!("Player {} was offline {}h, returning to town.", saved.identity, offline_hours);
+    } else if saved.health <= 0 {
+        spacetimedb::log::info!("Player {} logged out dead, respawning.", saved.identity);
+    } else if bounds::world_bounds(ctx).contains(&saved.position) {
+        // The terrain and obstacles may have changed while the player was away
+        let position = Terrain::new(ctx).snap_to_ground(&saved.position);
+        if !CollisionWorld::new(ctx).is_blocked(&position) {
+            return (position, saved.rotation.clone());
+        }
+        spacetimedb::log::warn!(
+            "Saved position {:?} for {} is inside an obstacle, using a spawn point.",
+            position,
+            saved.identity
+        );
+    } else {
+        spacetimedb::log::warn!(
+            "Saved position {:?} for {} is invalid, using a spawn point.",
+            saved.position,
+            saved.identity
+        );
+    }
+    select_spawn_point(ctx, saved.identity, team)
+}
+
+// Spawn point usable by `team` closest to `position` (horizontally), for returning players
+// that left the world bounds. Falls back to select_spawn_point if the position is unusable.
+pub fn nearest_spawn_point(ctx: &ReducerContext, identity: Identity, position: &Vector3, team: u32) -> (Vector3, Vector3) {
+    if position.x.is_finite() && position.z.is_finite() {
+        let horizontal_distance_sq = |p: &SpawnPoint| {
+            let dx = p.position.x - position.x;
+            let dz = p.position.z - position.z;
+            dx * dx + dz * dz
+        };
+        let nearest = ctx.db.spawn_point()
+            .iter()
+            .filter(|p| team == 0 || p.team == team || p.team == 0)
+            .min_by(|a, b| horizontal_distance_sq(a).total_cmp(&horizontal_distance_sq(b)));
+        if let Some(point) = nearest {
+            return (Terrain::new(ctx).snap_to_ground(&point.position), Vector3 { x: 0.0, y: point.yaw, z: 0.0 });
+        }
+    }
+    select_spawn_point(ctx, identity, team)
+}
+
+// Puts the player into the dead state and schedules the respawn
+pub fn kill_player(ctx: &ReducerContext, player: &mut PlayerData) {
+    let delay_micros = (config(ctx).respawn_delay_seconds * 1_000_000.0) as i64;
+    spacetimedb::log::info!("Player {} died.", player.identity);
+    player.health = 0;
+    player.is_dead = true;
+    player.respawn_at = Timestamp::from_micros_since_unix_epoch(ctx.timestamp.to_micros_since_unix_epoch() + delay_micros);
+    // Dead players don't act on held keys; jump keeps its state so a key still held at
+    // respawn doesn't count as a new press
+    player.input.forward = false;
+    player.input.backward = false;
+    player.input.left = false;
+    player.input.right = false;
+    player.input.sprint = false;
+    player.jump_requested = false;
+    player.is_moving = false;
+    player.is_running = false;
+    spells::interrupt_cast(ctx, player, "death");
+    animation::play_action(player, AnimationState::Dead, ctx.timestamp);
+}
+
+// Respawns dead players whose respawn timer has elapsed (called from game_tick)
+pub fn process_respawns(ctx: &ReducerContext) {
+    let ready: Vec<PlayerData> = ctx.db.player()
+        .iter()
+        .filter(|p| p.is_dead && p.respawn_at <= ctx.timestamp)
+        .collect();
+
+    for mut player in ready {
+        let (position, rotation) = select_spawn_point(ctx, player.identity, player.team);
+        player.position = position;
+        player.rotation = rotation;
+        player_logic::reset_vertical(&mut player);
+        player.health = player.max_health;
+        player.mana = player.max_mana;
+        player.is_dead = false;
+        player.last_update = ctx.timestamp;
+        animation::reset_animation(&mut player, AnimationState::Idle, ctx.timestamp);
+        spacetimedb::log::info!("Player {} respawned.", player.identity);
+        let player = ctx.db.player().identity().update(player);
+        crate::publish_input_ack(ctx, &player);
+    }
+}
@@ -0,0 +1,298 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - spells.rs
+ *
+ * Spell casting for InputState.cast_spell and the cast_spell reducer.
+ *
+ * Key components:
+ * - SpellDefinition: Data-driven spells (mana cost, cast time, cooldown, range, effect)
+ * - ActiveCast: In-progress casts, public so clients can draw cast bars
+ * - SpellCooldown: Per-caster cooldowns, public for UI
+ * - begin_cast: Validates and starts a cast (mana is checked here and deducted on completion)
+ * - update_casts: Completes finished casts (called from game_tick)
+ * - interrupt_cast: Cancels a cast when the caster moves or takes damage
+ * - regenerate_mana: Periodic mana regeneration (called from game_tick)
+ *
+ * When modifying:
+ * - Add spells in init_spells; IDs are stable so clients can reference them
+ * - A class may only cast spells listed in its ClassDefinition.spell_ids
+ * - Damage spells without an explicit target pick the nearest player in front of the caster
+ *
+ * Related files:
+ * - lib.rs: cast_spell reducer, input handling and game_tick
+ * - combat.rs: apply_damage is shared with melee
+ */
+
+use spacetimedb::{Identity, ReducerContext, SpacetimeType, Table, Timestamp};
+
+use crate::animation;
+use crate::classes;
+use crate::combat;
+use crate::common::{AnimationState, MANA_REGEN_PER_INTERVAL};
+use crate::player_logic;
+use crate::{player, PlayerData};
+
+#[derive(SpacetimeType, Clone, Copy, Debug, PartialEq)]
+pub enum SpellEffect {
+    Damage,
+    Heal,
+}
+
+#[spacetimedb::table(name = spell_definition, public)]
+#[derive(Clone)]
+pub struct SpellDefinition {
+    #[primary_key]
+    pub spell_id: u32,
+    pub name: String,
+    pub mana_cost: i32,
+    pub cast_time_seconds: f32,
+    pub cooldown_seconds: f32,
+    pub range: f32,
+    pub effect: SpellEffect,
+    pub amount: i32,
+}
+
+#[spacetimedb::table(name = active_cast, public)]
+#[derive(Clone)]
+pub struct ActiveCast {
+    #[primary_key]
+    pub caster: Identity,
+    pub spell_id: u32,
+    pub target: Identity,
+    pub started_at: Timestamp,
+    pub completes_at: Timestamp,
+}
+
+#[spacetimedb::table(name = spell_cooldown, public)]
+#[derive(Clone)]
+pub struct SpellCooldown {
+    #[primary_key]
+    #[auto_inc]
+    pub cooldown_id: u64,
+    #[index(btree)]
+    pub caster: Identity,
+    pub spell_id: u32,
+    pub ready_at: Timestamp,
+}
+
+pub const SPELL_FIREBALL: u32 = 1;
+pub const SPELL_HEAL: u32 = 2;
+
+// Half-angle (radians) of the cone used to auto-target damage spells
+const AUTO_TARGET_HALF_ANGLE: f32 = 0.5;
+
+// Called from init: seeds the default spell book
+pub fn init_spells(ctx: &ReducerContext) {
+    let defaults = [
+        SpellDefinition {
+            spell_id: SPELL_FIREBALL,
+            name: "Fireball".to_string(),
+            mana_cost: 20,
+            cast_time_seconds: 1.0,
+            cooldown_seconds: 2.0,
+            range: 20.0,
+            effect: SpellEffect::Damage,
+            amount: 25,
+        },
+        SpellDefinition {
+            spell_id: SPELL_HEAL,
+            name: "Heal".to_string(),
+            mana_cost: 15,
+            cast_time_seconds: 1.5,
+            cooldown_seconds: 5.0,
+            range: 10.0,
+            effect: SpellEffect::Heal,
+            amount: 20,
+        },
+    ];
+    for spell in defaults {
+        if ctx.db.spell_definition().spell_id().find(spell.spell_id).is_none() {
+            ctx.db.spell_definition().insert(spell);
+        }
+    }
+}
+
+// Validates and starts casting `spell_id`. The target defaults to self for heals and to
+// the nearest player in front of the caster for damage spells.
+pub fn begin_cast(ctx: &ReducerContext, caster: &mut PlayerData, spell_id: u32, target: Option<Identity>) -> Result<(), String> {
+    if caster.is_dead {
+        return Err("Cannot cast while dead".to_string());
+    }
+    if caster.is_moving {
+        return Err("Cannot cast while moving".to_string());
+    }
+    if ctx.db.active_cast().caster().find(caster.identity).is_some() {
+        return Err("Already casting".to_string());
+    }
+    let spell = ctx.db.spell_definition().spell_id().find(spell_id)
+        .ok_or_else(|| format!("Unknown spell {}", spell_id))?;
+    let class = classes::class_stats(ctx, &caster.character_class);
+    if !class.spell_ids.contains(&spell_id) || !classes::allows_animation(&class, "cast") {
+        return Err(format!("{} cannot cast {}", caster.character_class, spell.name));
+    }
+    if caster.mana < spell.mana_cost {
+        return Err(format!("Not enough mana for {}", spell.name));
+    }
+    if let Some(cooldown) = find_cooldown(ctx, caster.identity, spell_id) {
+        if cooldown.ready_at > ctx.timestamp {
+            return Err(format!("{} is on cooldown", spell.name));
+        }
+    }
+
+    let target = match (target, spell.effect) {
+        (Some(identity), _) => identity,
+        (None, SpellEffect::Heal) => caster.identity,
+        (None, SpellEffect::Damage) => find_auto_target(ctx, caster, spell.range)
+            .ok_or_else(|| "No target in range".to_string())?,
+    };
+    if target != caster.identity {
+        let target_player = ctx.db.player().identity().find(target)
+            .ok_or_else(|| "Target is not in the world".to_string())?;
+        if distance(caster, &target_player) > spell.range {
+            return Err("Target is out of range".to_string());
+        }
+    }
+
+    // The cast animation lasts until the spell resolves
+    if !animation::play_action_for(caster, AnimationState::Cast, spell.cast_time_seconds, ctx.timestamp) {
+        return Err("Cannot cast right now".to_string());
+    }
+    let cast_micros = (spell.cast_time_seconds * 1_000_000.0) as i64;
+    ctx.db.active_cast().insert(ActiveCast {
+        caster: caster.identity,
+        spell_id,
+        target,
+        started_at: ctx.timestamp,
+        completes_at: Timestamp::from_micros_since_unix_epoch(ctx.timestamp.to_micros_since_unix_epoch() + cast_micros),
+    });
+    Ok(())
+}
+
+// Cancels the player's cast, if any. Returns true if a cast was interrupted.
+pub fn interrupt_cast(ctx: &ReducerContext, player: &mut PlayerData, reason: &str) -> bool {
+    if !ctx.db.active_cast().caster().delete(player.identity) {
+        return false;
+    }
+    spacetimedb::log::debug!("Cast by {} interrupted ({})", player.identity, reason);
+    if player.animation_state == AnimationState::Cast {
+        animation::reset_animation(player, AnimationState::Idle, ctx.timestamp);
+        animation::update_animation(player, ctx.timestamp);
+    }
+    true
+}
+
+// Completes casts whose cast time has elapsed (called from game_tick)
+pub fn update_casts(ctx: &ReducerContext) {
+    let finished: Vec<ActiveCast> = ctx.db.active_cast()
+        .iter()
+        .filter(|c| c.completes_at <= ctx.timestamp)
+        .collect();
+
+    for cast in finished {
+        ctx.db.active_cast().caster().delete(cast.caster);
+        if let Err(e) = complete_cast(ctx, &cast) {
+            spacetimedb::log::debug!("Cast by {} fizzled: {}", cast.caster, e);
+        }
+    }
+}
+
+fn complete_cast(ctx: &ReducerContext, cast: &ActiveCast) -> Result<(), String> {
+    let spell = ctx.db.spell_definition().spell_id().find(cast.spell_id)
+        .ok_or_else(|| "Spell no longer exists".to_string())?;
+    let mut caster = ctx.db.player().identity().find(cast.caster)
+        .ok_or_else(|| "Caster left the world".to_string())?;
+    if caster.is_dead {
+        return Err("Caster died".to_string());
+    }
+    if caster.mana < spell.mana_cost {
+        return Err("Not enough mana".to_string());
+    }
+
+    caster.mana -= spell.mana_cost;
+    set_cooldown(ctx, caster.identity, &spell);
+
+    if cast.target == caster.identity {
+        apply_effect(ctx, &spell, caster.identity, &mut caster);
+        ctx.db.player().identity().update(caster);
+        return Ok(());
+    }
+
+    let mut target = ctx.db.player().identity().find(cast.target)
+        .ok_or_else(|| "Target left the world".to_string())?;
+    if target.is_dead || distance(&caster, &target) > spell.range {
+        ctx.db.player().identity().update(caster);
+        return Err("Target is no longer valid".to_string());
+    }
+    apply_effect(ctx, &spell, caster.identity, &mut target);
+    ctx.db.player().identity().update(caster);
+    ctx.db.player().identity().update(target);
+    Ok(())
+}
+
+fn apply_effect(ctx: &ReducerContext, spell: &SpellDefinition, caster: Identity, target: &mut PlayerData) {
+    match spell.effect {
+        SpellEffect::Damage => combat::apply_damage(ctx, caster, target, spell.amount),
+        SpellEffect::Heal => {
+            target.health = (target.health + spell.amount).min(target.max_health);
+        }
+    }
+}
+
+fn find_cooldown(ctx: &ReducerContext, caster: Identity, spell_id: u32) -> Option<SpellCooldown> {
+    ctx.db.spell_cooldown().caster().filter(&caster).find(|c| c.spell_id == spell_id)
+}
+
+fn set_cooldown(ctx: &ReducerContext, caster: Identity, spell: &SpellDefinition) {
+    let ready_at = Timestamp::from_micros_since_unix_epoch(
+        ctx.timestamp.to_micros_since_unix_epoch() + (spell.cooldown_seconds * 1_000_000.0) as i64,
+    );
+    match find_cooldown(ctx, caster, spell.spell_id) {
+        Some(mut cooldown) => {
+            cooldown.ready_at = ready_at;
+            ctx.db.spell_cooldown().cooldown_id().update(cooldown);
+        }
+        None => {
+            ctx.db.spell_cooldown().insert(SpellCooldown {
+                cooldown_id: 0,
+                caster,
+                spell_id: spell.spell_id,
+                ready_at,
+            });
+        }
+    }
+}
+
+fn find_auto_target(ctx: &ReducerContext, caster: &PlayerData, range: f32) -> Option<Identity> {
+    let facing = player_logic::facing_direction(&caster.rotation);
+    let min_cos = AUTO_TARGET_HALF_ANGLE.cos();
+    ctx.db.player()
+        .iter()
+        .filter(|p| p.identity != caster.identity && !p.is_dead)
+        .filter_map(|p| {
+            let dx = p.position.x - caster.position.x;
+            let dz = p.position.z - caster.position.z;
+            let dist = (dx * dx + dz * dz).sqrt();
+            let in_cone = dist < 0.01 || (dx * facing.x + dz * facing.z) / dist >= min_cos;
+            (dist <= range && in_cone).then_some((p.identity, dist))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(identity, _)| identity)
+}
+
+fn distance(a: &PlayerData, b: &PlayerData) -> f32 {
+    let dx = b.position.x - a.position.x;
+    let dy = b.position.y - a.position.y;
+    let dz = b.position.z - a.position.z;
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+// Restores MANA_REGEN_PER_INTERVAL mana to every living player (called from game_tick)
+pub fn regenerate_mana(ctx: &ReducerContext) {
+    let players: Vec<PlayerData> = ctx.db.player()
+        .iter()
+        .filter(|p| !p.is_dead && p.mana < p.max_mana)
+        .collect();
+    for mut player in players {
+        player.mana = (player.mana + MANA_REGEN_PER_INTERVAL).min(player.max_mana);
+        ctx.db.player().identity().update(player);
+    }
+}
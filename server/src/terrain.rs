@@ -0,0 +1,274 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - terrain.rs
+ *
+ * Heightmap terrain shared by server movement and client rendering.
+ *
+ * Key components:
+ * - TerrainConfig: Public singleton describing the grid (chunk size, cell size, extent, max slope)
+ * - TerrainChunk: Public (chunk_size + 1)^2 height samples per chunk, row-major along X then Z
+ * - Terrain: Per-reducer sampler that loads chunks on demand and caches them
+ *   - height_at: Bilinear height at any world X/Z (interpolation in sample_bilinear)
+ *   - is_walkable: Slope check between two points (uphill steeper than max_slope_degrees is blocked,
+ *     measured from the player's feet so airborne players can land on higher ground)
+ *   - snap_to_ground: Places a position on the terrain surface
+ * - terrain_half_extent: Size of the heightmap, used for the default world bounds
+ *
+ * Grid layout:
+ * - Chunk (cx, cz) covers X in [cx, cx + 1) * chunk_size * cell_size (same for Z)
+ * - Chunks exist for cx, cz in [-chunk_radius, chunk_radius); outside that the ground is flat
+ *   at default_height
+ * - Neighbouring chunks duplicate their shared edge so each chunk can be meshed on its own
+ *
+ * When modifying:
+ * - Clients sample and mesh the same tables, so keep the interpolation in sync with
+ *   client/src/components/Terrain.tsx
+ * - init_terrain only generates chunks when none exist; replace rows with `spacetime sql`
+ *   or a tool to load a custom heightmap
+ *
+ * Related files:
+ * - player_logic.rs: calculate_new_position applies terrain height and slope limits
+ * - spawn.rs: Spawn and restore positions are snapped to the ground
+ * - bounds.rs: Default world bounds cover exactly the heightmap
+ */
+
+use spacetimedb::{ReducerContext, Table};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::common::Vector3;
+
+const DEFAULT_CHUNK_SIZE: u32 = 16;
+const DEFAULT_CELL_SIZE: f32 = 2.0;
+const DEFAULT_CHUNK_RADIUS: i32 = 4;
+const DEFAULT_MAX_SLOPE_DEGREES: f32 = 45.0;
+// Generated terrain is flat inside this radius (the spawn ring) and blends to hills beyond it
+const FLAT_RADIUS: f32 = 15.0;
+const FLAT_BLEND_DISTANCE: f32 = 20.0;
+// A steep-sided plateau that demonstrates the slope limit
+const PLATEAU_CENTER: (f32, f32) = (60.0, 60.0);
+const PLATEAU_RADIUS: f32 = 12.0;
+const PLATEAU_HEIGHT: f32 = 6.0;
+const PLATEAU_EDGE_WIDTH: f32 = 2.0;
+
+#[spacetimedb::table(name = terrain_config, public)]
+#[derive(Clone)]
+pub struct TerrainConfig {
+    #[primary_key]
+    pub id: u32,
+    // Cells per chunk side; each chunk stores (chunk_size + 1)^2 samples
+    pub chunk_size: u32,
+    // World units between two samples
+    pub cell_size: f32,
+    pub chunk_radius: i32,
+    pub default_height: f32,
+    pub max_slope_degrees: f32,
+}
+
+#[spacetimedb::table(name = terrain_chunk, public)]
+#[derive(Clone)]
+pub struct TerrainChunk {
+    #[primary_key]
+    pub chunk_id: u64,
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub heights: Vec<f32>,
+}
+
+impl TerrainConfig {
+    // Distance from the origin to the edge of the generated chunks on X and Z
+    pub fn half_extent(&self) -> f32 {
+        self.chunk_radius as f32 * self.chunk_size as f32 * self.cell_size
+    }
+}
+
+// Half extent of the heightmap in world units (the default config's when none exists yet)
+pub fn terrain_half_extent(ctx: &ReducerContext) -> f32 {
+    match ctx.db.terrain_config().id().find(0) {
+        Some(config) => config.half_extent(),
+        None => DEFAULT_CHUNK_RADIUS as f32 * DEFAULT_CHUNK_SIZE as f32 * DEFAULT_CELL_SIZE,
+    }
+}
+
+// Packs chunk coordinates into the chunk_id primary key
+pub fn chunk_id(chunk_x: i32, chunk_z: i32) -> u64 {
+    ((chunk_x as u32 as u64) << 32) | chunk_z as u32 as u64
+}
+
+// Called from init: creates the config and generates the default heightmap
+pub fn init_terrain(ctx: &ReducerContext) {
+    let config = match ctx.db.terrain_config().id().find(0) {
+        Some(config) => config,
+        None => ctx.db.terrain_config().insert(TerrainConfig {
+            id: 0,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            cell_size: DEFAULT_CELL_SIZE,
+            chunk_radius: DEFAULT_CHUNK_RADIUS,
+            default_height: 0.0,
+            max_slope_degrees: DEFAULT_MAX_SLOPE_DEGREES,
+        }),
+    };
+    if ctx.db.terrain_chunk().count() > 0 {
+        return;
+    }
+
+    spacetimedb::log::info!("[INIT] Generating {}x{} terrain chunks...", config.chunk_radius * 2, config.chunk_radius * 2);
+    let samples = config.chunk_size + 1;
+    for chunk_x in -config.chunk_radius..config.chunk_radius {
+        for chunk_z in -config.chunk_radius..config.chunk_radius {
+            let mut heights = Vec::with_capacity((samples * samples) as usize);
+            for j in 0..samples {
+                for i in 0..samples {
+                    let x = (chunk_x * config.chunk_size as i32 + i as i32) as f32 * config.cell_size;
+                    let z = (chunk_z * config.chunk_size as i32 + j as i32) as f32 * config.cell_size;
+                    heights.push(config.default_height + generated_height(x, z));
+                }
+            }
+            ctx.db.terrain_chunk().insert(TerrainChunk {
+                chunk_id: chunk_id(chunk_x, chunk_z),
+                chunk_x,
+                chunk_z,
+                heights,
+            });
+        }
+    }
+}
+
+// Deterministic rolling hills, flattened around the origin, plus one steep plateau
+fn generated_height(x: f32, z: f32) -> f32 {
+    let hills = 3.0 * (x * 0.05).sin() * (z * 0.04).cos() + 1.5 * (x * 0.13 + 1.7).sin() * (z * 0.11 + 0.4).sin();
+    let distance = (x * x + z * z).sqrt();
+    let blend = ((distance - FLAT_RADIUS) / FLAT_BLEND_DISTANCE).clamp(0.0, 1.0);
+    let smooth_blend = blend * blend * (3.0 - 2.0 * blend);
+
+    let dx = x - PLATEAU_CENTER.0;
+    let dz = z - PLATEAU_CENTER.1;
+    let from_edge = PLATEAU_RADIUS - (dx * dx + dz * dz).sqrt();
+    let plateau = PLATEAU_HEIGHT * (from_edge / PLATEAU_EDGE_WIDTH).clamp(0.0, 1.0);
+
+    hills * smooth_blend + plateau
+}
+
+// Bilinear interpolation in a row-major grid of `samples` x `samples` heights, at grid
+// coordinates (local_x, local_z) within 0..=samples - 1; missing samples read as `default_height`
+fn sample_bilinear(heights: &[f32], samples: usize, local_x: f32, local_z: f32, default_height: f32) -> f32 {
+    let i0 = (local_x.floor() as usize).min(samples - 2);
+    let j0 = (local_z.floor() as usize).min(samples - 2);
+    let fx = local_x - i0 as f32;
+    let fz = local_z - j0 as f32;
+    let sample = |i: usize, j: usize| heights.get(j * samples + i).copied().unwrap_or(default_height);
+
+    let near = sample(i0, j0) * (1.0 - fx) + sample(i0 + 1, j0) * fx;
+    let far = sample(i0, j0 + 1) * (1.0 - fx) + sample(i0 + 1, j0 + 1) * fx;
+    near * (1.0 - fz) + far * fz
+}
+
+// Height sampler for one reducer call; chunks are read from the table at most once
+pub struct Terrain<'a> {
+    ctx: &'a ReducerContext,
+    config: Option<TerrainConfig>,
+    chunks: RefCell<HashMap<u64, Option<Vec<f32>>>>,
+}
+
+impl<'a> Terrain<'a> {
+    pub fn new(ctx: &'a ReducerContext) -> Self {
+        Terrain {
+            ctx,
+            config: ctx.db.terrain_config().id().find(0),
+            chunks: RefCell::new(HashMap::new()),
+        }
+    }
+
+    // Ground height at world (x, z), bilinearly interpolated between samples
+    pub fn height_at(&self, x: f32, z: f32) -> f32 {
+        let Some(config) = &self.config else {
+            return 0.0;
+        };
+        let chunk_span = config.chunk_size as f32;
+        let grid_x = x / config.cell_size;
+        let grid_z = z / config.cell_size;
+        let chunk_x = (grid_x / chunk_span).floor() as i32;
+        let chunk_z = (grid_z / chunk_span).floor() as i32;
+        if chunk_x < -config.chunk_radius || chunk_x >= config.chunk_radius
+            || chunk_z < -config.chunk_radius || chunk_z >= config.chunk_radius
+        {
+            return config.default_height;
+        }
+
+        let mut cache = self.chunks.borrow_mut();
+        let heights = cache.entry(chunk_id(chunk_x, chunk_z)).or_insert_with(|| {
+            self.ctx.db.terrain_chunk().chunk_id().find(chunk_id(chunk_x, chunk_z)).map(|c| c.heights)
+        });
+        let Some(heights) = heights else {
+            return config.default_height;
+        };
+
+        let local_x = (grid_x - chunk_x as f32 * chunk_span).clamp(0.0, chunk_span);
+        let local_z = (grid_z - chunk_z as f32 * chunk_span).clamp(0.0, chunk_span);
+        sample_bilinear(heights, config.chunk_size as usize + 1, local_x, local_z, config.default_height)
+    }
+
+    // False if moving from `from` to `to` climbs steeper than max_slope_degrees. The climb is
+    // measured from whichever is higher, the ground at `from` or `from.y` itself (airborne).
+    pub fn is_walkable(&self, from: &Vector3, to: &Vector3) -> bool {
+        let Some(config) = &self.config else {
+            return true;
+        };
+        let dx = to.x - from.x;
+        let dz = to.z - from.z;
+        let run = (dx * dx + dz * dz).sqrt();
+        let rise = self.height_at(to.x, to.z) - self.height_at(from.x, from.z).max(from.y);
+        if run < 0.0001 || rise <= 0.0 {
+            return true;
+        }
+        rise / run <= config.max_slope_degrees.to_radians().tan()
+    }
+
+    pub fn snap_to_ground(&self, position: &Vector3) -> Vector3 {
+        Vector3 {
+            x: position.x,
+            y: self.height_at(position.x, position.z),
+            z: position.z,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3x3 samples: heights rise by 1 per cell on X and by 10 per cell on Z
+    const GRID: [f32; 9] = [0.0, 1.0, 2.0, 10.0, 11.0, 12.0, 20.0, 21.0, 22.0];
+
+    #[test]
+    fn samples_are_exact_on_grid_points() {
+        assert_eq!(sample_bilinear(&GRID, 3, 0.0, 0.0, -1.0), 0.0);
+        assert_eq!(sample_bilinear(&GRID, 3, 1.0, 2.0, -1.0), 21.0);
+        // The far edge uses the last cell rather than reading past the grid
+        assert_eq!(sample_bilinear(&GRID, 3, 2.0, 2.0, -1.0), 22.0);
+    }
+
+    #[test]
+    fn heights_interpolate_between_samples() {
+        assert!((sample_bilinear(&GRID, 3, 0.5, 0.0, -1.0) - 0.5).abs() < 1e-5);
+        assert!((sample_bilinear(&GRID, 3, 1.5, 0.5, -1.0) - 6.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn missing_samples_use_the_default_height() {
+        assert_eq!(sample_bilinear(&GRID[..4], 3, 2.0, 2.0, -1.0), -1.0);
+    }
+
+    #[test]
+    fn chunk_ids_are_unique_for_negative_coordinates() {
+        let ids = [chunk_id(0, 0), chunk_id(-1, 0), chunk_id(0, -1), chunk_id(-1, -1), chunk_id(1, -1)];
+        for (index, id) in ids.iter().enumerate() {
+            assert!(!ids[index + 1..].contains(id));
+        }
+    }
+
+    #[test]
+    fn terrain_is_flat_around_the_origin() {
+        assert_eq!(generated_height(0.0, 0.0), 0.0);
+        assert_eq!(generated_height(FLAT_RADIUS * 0.5, 0.0), 0.0);
+    }
+}
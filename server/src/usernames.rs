@@ -0,0 +1,181 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - usernames.rs
+ *
+ * Name policy applied to character names and profile (account) names.
+ *
+ * Key components:
+ * - ReservedName: Names (or name fragments) nobody may register, e.g. to prevent impersonation
+ * - check_username_format: Length and character set rules, without touching the database
+ * - validate_username: Format, reservation and uniqueness checks
+ * - add_reserved_name / remove_reserved_name: Admin-only editing of the reserved list
+ *
+ * Rules:
+ * - USERNAME_MIN_LENGTH..=USERNAME_MAX_LENGTH characters after trimming
+ * - ASCII letters, digits, '_' and '-' only, starting with a letter or digit
+ * - Case-insensitive unique: a name used by one identity's characters or profile is
+ *   unavailable to every other identity
+ *
+ * When modifying:
+ * - Reserved names are stored and compared trimmed and lowercase (normalize_reserved_name);
+ *   seed defaults in init_reserved_names
+ * - Admins edit the list with admin_add_reserved_name / admin_remove_reserved_name
+ *
+ * Related files:
+ * - characters.rs: create_character returns these errors to the client
+ * - profiles.rs: update_profile validates renames
+ * - admin.rs: require_role and the audit log used by the reserved-name reducers
+ * - client/src/components/JoinGameDialog.tsx: Displays the error message
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table};
+
+use crate::admin::{self, Role};
+use crate::characters::character_roster;
+use crate::profiles::player_profile;
+
+pub const USERNAME_MIN_LENGTH: usize = 3;
+pub const USERNAME_MAX_LENGTH: usize = 16;
+
+#[spacetimedb::table(name = reserved_name)]
+#[derive(Clone)]
+pub struct ReservedName {
+    #[primary_key]
+    pub name: String,
+    // When true, any username containing `name` is rejected
+    pub match_substring: bool,
+    pub reason: String,
+}
+
+// Canonical form of a reserved name, used both when storing and when matching
+pub fn normalize_reserved_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+// Called from init: seeds names that would let players impersonate staff or the system
+pub fn init_reserved_names(ctx: &ReducerContext) {
+    let defaults = [
+        ("admin", true, "Impersonates staff"),
+        ("moderator", true, "Impersonates staff"),
+        ("server", false, "Impersonates the system"),
+        ("system", false, "Impersonates the system"),
+    ];
+    for (name, match_substring, reason) in defaults {
+        if ctx.db.reserved_name().name().find(&name.to_string()).is_none() {
+            ctx.db.reserved_name().insert(ReservedName {
+                name: name.to_string(),
+                match_substring,
+                reason: reason.to_string(),
+            });
+        }
+    }
+}
+
+impl ReservedName {
+    // Whether this entry rejects `lowered`, a lowercased username
+    pub fn matches(&self, lowered: &str) -> bool {
+        let reserved = normalize_reserved_name(&self.name);
+        if self.match_substring { lowered.contains(&reserved) } else { lowered == reserved }
+    }
+}
+
+// Checks the length and character rules. Returns the trimmed name.
+pub fn check_username_format(username: &str) -> Result<&str, String> {
+    let name = username.trim();
+    let length = name.chars().count();
+    if length < USERNAME_MIN_LENGTH || length > USERNAME_MAX_LENGTH {
+        return Err(format!(
+            "Name must be between {} and {} characters",
+            USERNAME_MIN_LENGTH, USERNAME_MAX_LENGTH
+        ));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err("Name may only contain letters, numbers, '_' and '-'".to_string());
+    }
+    if !name.chars().next().is_some_and(|c| c.is_ascii_alphanumeric()) {
+        return Err("Name must start with a letter or number".to_string());
+    }
+    Ok(name)
+}
+
+// Validates and normalises a requested username for `identity`.
+// Returns the trimmed name, or an error message suitable for showing to the player.
+pub fn validate_username(ctx: &ReducerContext, identity: Identity, username: &str) -> Result<String, String> {
+    let name = check_username_format(username)?;
+
+    let lowered = name.to_lowercase();
+    if ctx.db.reserved_name().iter().any(|r| r.matches(&lowered)) {
+        return Err(format!("The name '{}' is not available", name));
+    }
+
+    let taken_by_character = ctx.db.character_roster()
+        .iter()
+        .any(|c| c.owner != identity && c.name.to_lowercase() == lowered);
+    let taken_by_profile = ctx.db.player_profile()
+        .iter()
+        .any(|p| p.identity != identity && p.username.to_lowercase() == lowered);
+    if taken_by_character || taken_by_profile {
+        return Err(format!("The name '{}' is already taken", name));
+    }
+
+    Ok(name.to_string())
+}
+
+pub fn add_reserved_name(ctx: &ReducerContext, name: String, match_substring: bool, reason: String) -> Result<(), String> {
+    admin::require_role(ctx, Role::Admin)?;
+    let name = normalize_reserved_name(&name);
+    if name.is_empty() {
+        return Err("Reserved name can't be empty".to_string());
+    }
+    let entry = ReservedName { name: name.clone(), match_substring, reason: reason.clone() };
+    if ctx.db.reserved_name().name().find(&name).is_some() {
+        ctx.db.reserved_name().name().update(entry);
+    } else {
+        ctx.db.reserved_name().insert(entry);
+    }
+    let kind = if match_substring { "substring" } else { "exact" };
+    admin::audit(ctx, "add_reserved_name", None, format!("{} ({}): {}", name, kind, reason));
+    Ok(())
+}
+
+pub fn remove_reserved_name(ctx: &ReducerContext, name: String) -> Result<(), String> {
+    admin::require_role(ctx, Role::Admin)?;
+    let name = normalize_reserved_name(&name);
+    if !ctx.db.reserved_name().name().delete(&name) {
+        return Err(format!("'{}' is not a reserved name", name));
+    }
+    admin::audit(ctx, "remove_reserved_name", None, name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reserved(name: &str, match_substring: bool) -> ReservedName {
+        ReservedName { name: name.to_string(), match_substring, reason: String::new() }
+    }
+
+    #[test]
+    fn reserved_names_are_normalized() {
+        assert_eq!(normalize_reserved_name("  AdMin "), "admin");
+    }
+
+    #[test]
+    fn reserved_names_match_exactly_or_by_substring() {
+        assert!(reserved("server", false).matches("server"));
+        assert!(!reserved("server", false).matches("myserver"));
+        assert!(reserved("admin", true).matches("theadmin42"));
+        // Entries stored before normalization still match
+        assert!(reserved(" Admin", true).matches("admin_bob"));
+    }
+
+    #[test]
+    fn username_format_rules() {
+        assert_eq!(check_username_format("  Bob_1 "), Ok("Bob_1"));
+        assert!(check_username_format("ab").is_err());
+        assert!(check_username_format(&"a".repeat(USERNAME_MAX_LENGTH + 1)).is_err());
+        assert!(check_username_format("bad name").is_err());
+        assert!(check_username_format("_bob").is_err());
+        assert!(check_username_format("bøb").is_err());
+    }
+}
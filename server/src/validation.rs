@@ -0,0 +1,290 @@
+/**
+ * Vibe Coding Starter Pack: 3D Multiplayer - validation.rs
+ *
+ * Movement validation and violation tracking for update_player_input.
+ *
+ * Key components:
+ * - MovementViolation: Per-identity log of rejected or clamped movement within the violation window
+ * - MovementKick: Identities kicked for repeated violations, refused by enter_world until the cooldown ends
+ * - MovementValidationConfig: Admin-settable singleton with the teleport factor and kick thresholds
+ * - validate_movement: Checks a proposed position against the max plausible horizontal displacement
+ *   and reports whether it recorded a violation
+ * - should_kick: Applies the violation threshold within a sliding time window
+ * - record_kick / kick_cooldown_remaining: Kick cooldown bookkeeping
+ * - prune_violations: Drops violations older than the window and finished cooldowns (from game_tick)
+ *
+ * When modifying:
+ * - The slack lives in common.rs (MOVEMENT_VALIDATION_SLACK, tied to the client hint tolerance);
+ *   the other thresholds are changed at runtime with admin_set_movement_validation, and their
+ *   defaults are the DEFAULT_* constants in common.rs
+ * - Displacements beyond teleport_distance_factor times the allowed distance are rejected,
+ *   smaller overshoots are clamped along the direction of travel
+ * - Only a move that records a violation can trigger a kick; collision, terrain and bounds
+ *   corrections applied afterwards are not violations
+ *
+ * Related files:
+ * - lib.rs: Calls validate_movement from update_player_input and kicks offenders, calls
+ *   init_validation
+ * - characters.rs: enter_world enforces the kick cooldown
+ * - common.rs: Speed constants and validation thresholds
+ */
+
+use spacetimedb::{Identity, ReducerContext, Table, Timestamp};
+
+use crate::admin::{self, Role};
+use crate::common::{
+    Vector3, PLAYER_SPEED, SPRINT_MULTIPLIER, MOVEMENT_VALIDATION_SLACK, DEFAULT_TELEPORT_DISTANCE_FACTOR,
+    DEFAULT_VIOLATION_WINDOW_SECONDS, DEFAULT_VIOLATION_KICK_THRESHOLD, DEFAULT_KICK_COOLDOWN_SECONDS,
+};
+
+#[spacetimedb::table(name = movement_violation)]
+#[derive(Clone)]
+pub struct MovementViolation {
+    #[primary_key]
+    #[auto_inc]
+    pub violation_id: u64,
+    #[index(btree)]
+    pub identity: Identity,
+    pub timestamp: Timestamp,
+    pub reason: String,
+    pub attempted_distance: f32,
+    pub allowed_distance: f32,
+}
+
+#[spacetimedb::table(name = movement_kick)]
+#[derive(Clone)]
+pub struct MovementKick {
+    #[primary_key]
+    pub identity: Identity,
+    pub kicked_at: Timestamp,
+    pub cooldown_until: Timestamp,
+}
+
+#[spacetimedb::table(name = movement_validation_config)]
+#[derive(Clone)]
+pub struct MovementValidationConfig {
+    #[primary_key]
+    pub id: u32,
+    // Moves longer than this multiple of the allowed distance are rejected instead of clamped
+    pub teleport_distance_factor: f32,
+    // Sliding window over which violations are counted
+    pub violation_window_seconds: u32,
+    // Violations within the window that trigger an automatic kick
+    pub violation_kick_threshold: u32,
+    // How long a player kicked for violations is kept out of the world
+    pub kick_cooldown_seconds: u32,
+}
+
+fn default_config() -> MovementValidationConfig {
+    MovementValidationConfig {
+        id: 0,
+        teleport_distance_factor: DEFAULT_TELEPORT_DISTANCE_FACTOR,
+        violation_window_seconds: DEFAULT_VIOLATION_WINDOW_SECONDS,
+        violation_kick_threshold: DEFAULT_VIOLATION_KICK_THRESHOLD,
+        kick_cooldown_seconds: DEFAULT_KICK_COOLDOWN_SECONDS,
+    }
+}
+
+// Called from init: creates the default config
+pub fn init_validation(ctx: &ReducerContext) {
+    if ctx.db.movement_validation_config().id().find(0).is_none() {
+        ctx.db.movement_validation_config().insert(default_config());
+    }
+}
+
+fn config(ctx: &ReducerContext) -> MovementValidationConfig {
+    ctx.db.movement_validation_config()
+        .id()
+        .find(0)
+        .unwrap_or_else(default_config)
+}
+
+// Furthest a player of a class with `speed_multiplier` can legitimately travel in `elapsed_seconds`
+pub fn max_displacement(elapsed_seconds: f32, speed_multiplier: f32) -> f32 {
+    PLAYER_SPEED * speed_multiplier * SPRINT_MULTIPLIER * elapsed_seconds + MOVEMENT_VALIDATION_SLACK
+}
+
+// Returns the position that should be applied for a move from `previous` to `proposed`, and
+// whether a violation was recorded. Implausible moves are clamped or rejected and recorded as
+// violations. Only X/Z are checked; height follows the terrain and is set by the caller.
+pub fn validate_movement(
+    ctx: &ReducerContext,
+    identity: Identity,
+    previous: &Vector3,
+    proposed: Vector3,
+    elapsed_seconds: f32,
+    speed_multiplier: f32,
+) -> (Vector3, bool) {
+    let dx = proposed.x - previous.x;
+    let dz = proposed.z - previous.z;
+    let distance = (dx * dx + dz * dz).sqrt();
+    let allowed = max_displacement(elapsed_seconds, speed_multiplier);
+
+    if distance <= allowed {
+        return (proposed, false);
+    }
+
+    if distance > allowed * config(ctx).teleport_distance_factor {
+        record_violation(ctx, identity, "teleport", distance, allowed);
+        return (previous.clone(), true);
+    }
+
+    record_violation(ctx, identity, "speed", distance, allowed);
+    let scale = allowed / distance;
+    let clamped = Vector3 {
+        x: previous.x + dx * scale,
+        y: proposed.y,
+        z: previous.z + dz * scale,
+    };
+    (clamped, true)
+}
+
+fn record_violation(ctx: &ReducerContext, identity: Identity, reason: &str, attempted_distance: f32, allowed_distance: f32) {
+    spacetimedb::log::warn!(
+        "Movement violation ({}) by {}: moved {:.2} units, allowed {:.2}",
+        reason,
+        identity,
+        attempted_distance,
+        allowed_distance
+    );
+    ctx.db.movement_violation().insert(MovementViolation {
+        violation_id: 0,
+        identity,
+        timestamp: ctx.timestamp,
+        reason: reason.to_string(),
+        attempted_distance,
+        allowed_distance,
+    });
+}
+
+// True once an identity has reached the kick threshold of violations within the window
+pub fn should_kick(ctx: &ReducerContext, identity: Identity) -> bool {
+    let config = config(ctx);
+    let window_start = seconds_ago(ctx, config.violation_window_seconds);
+    let recent = ctx.db.movement_violation()
+        .identity()
+        .filter(&identity)
+        .filter(|v| v.timestamp >= window_start)
+        .count();
+    recent as u32 >= config.violation_kick_threshold
+}
+
+// Starts the kick cooldown for `identity` (the caller removes the character from the world)
+pub fn record_kick(ctx: &ReducerContext, identity: Identity) {
+    let kick = MovementKick {
+        identity,
+        kicked_at: ctx.timestamp,
+        cooldown_until: Timestamp::from_micros_since_unix_epoch(
+            ctx.timestamp.to_micros_since_unix_epoch() + config(ctx).kick_cooldown_seconds as i64 * 1_000_000,
+        ),
+    };
+    if ctx.db.movement_kick().identity().find(identity).is_some() {
+        ctx.db.movement_kick().identity().update(kick);
+    } else {
+        ctx.db.movement_kick().insert(kick);
+    }
+}
+
+// Whole seconds until a kicked identity may enter the world again, or None if it may now
+pub fn kick_cooldown_remaining(ctx: &ReducerContext, identity: Identity) -> Option<u64> {
+    let kick = ctx.db.movement_kick().identity().find(identity)?;
+    kick.cooldown_until
+        .duration_since(ctx.timestamp)
+        .map(|d| d.as_secs().max(1))
+}
+
+// Called from game_tick: deletes violations that left the window and finished cooldowns
+pub fn prune_violations(ctx: &ReducerContext) {
+    let window_start = seconds_ago(ctx, config(ctx).violation_window_seconds);
+    let expired: Vec<MovementViolation> = ctx.db.movement_violation()
+        .iter()
+        .filter(|v| v.timestamp < window_start)
+        .collect();
+    for violation in expired {
+        ctx.db.movement_violation().delete(violation);
+    }
+    let finished: Vec<MovementKick> = ctx.db.movement_kick()
+        .iter()
+        .filter(|k| k.cooldown_until <= ctx.timestamp)
+        .collect();
+    for kick in finished {
+        ctx.db.movement_kick().delete(kick);
+    }
+}
+
+pub fn set_config(
+    ctx: &ReducerContext,
+    teleport_distance_factor: f32,
+    violation_window_seconds: u32,
+    violation_kick_threshold: u32,
+    kick_cooldown_seconds: u32,
+) -> Result<(), String> {
+    admin::require_role(ctx, Role::Admin)?;
+    if !(teleport_distance_factor.is_finite() && teleport_distance_factor >= 1.0) {
+        return Err("Teleport distance factor must be at least 1".to_string());
+    }
+    if violation_window_seconds == 0 || violation_kick_threshold == 0 {
+        return Err("Violation window and kick threshold must be positive".to_string());
+    }
+    let config = MovementValidationConfig {
+        id: 0,
+        teleport_distance_factor,
+        violation_window_seconds,
+        violation_kick_threshold,
+        kick_cooldown_seconds,
+    };
+    if ctx.db.movement_validation_config().id().find(0).is_some() {
+        ctx.db.movement_validation_config().id().update(config);
+    } else {
+        ctx.db.movement_validation_config().insert(config);
+    }
+    admin::audit(
+        ctx,
+        "set_movement_validation",
+        None,
+        format!(
+            "teleport factor {}, {} violations in {}s, {}s cooldown",
+            teleport_distance_factor, violation_kick_threshold, violation_window_seconds, kick_cooldown_seconds
+        ),
+    );
+    Ok(())
+}
+
+fn seconds_ago(ctx: &ReducerContext, seconds: u32) -> Timestamp {
+    Timestamp::from_micros_since_unix_epoch(ctx.timestamp.to_micros_since_unix_epoch() - seconds as i64 * 1_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{InputState, CLIENT_POSITION_TOLERANCE};
+    use crate::player_logic;
+
+    #[test]
+    fn slack_covers_replacing_a_client_hint() {
+        assert!(max_displacement(0.0, 1.0) >= 2.0 * CLIENT_POSITION_TOLERANCE);
+    }
+
+    #[test]
+    fn sprinting_at_full_speed_is_allowed() {
+        let input = InputState {
+            forward: true, backward: false, left: true, right: false,
+            sprint: true, jump: false, attack: false, cast_spell: false,
+            sequence: 0
+        };
+        let rotation = Vector3 { x: 0.0, y: 0.4, z: 0.0 };
+        for speed_multiplier in [0.8, 1.0, 1.3] {
+            let velocity = player_logic::calculate_velocity(&rotation, &input, speed_multiplier);
+            let speed = (velocity.x * velocity.x + velocity.z * velocity.z).sqrt();
+            for elapsed in [0.016, 0.1, 0.25] {
+                assert!(speed * elapsed <= max_displacement(elapsed, speed_multiplier));
+            }
+        }
+    }
+
+    #[test]
+    fn allowance_grows_with_time_and_class_speed() {
+        assert!(max_displacement(0.2, 1.0) > max_displacement(0.1, 1.0));
+        assert!(max_displacement(0.1, 1.5) > max_displacement(0.1, 1.0));
+    }
+}